@@ -1,10 +1,10 @@
 use show::{
-    container::Row, graphics::Color, style::Border, Command, Indents, Model, Program, Size, Style, Subscriptions, View
+    container::Row, graphics::Color, style::Border, Command, Indents, Model, Program, Style, Subscriptions, View, WindowSettings
 };
 
 fn main() {
     let mut program = Program::new().unwrap();
-    program.run::<App>(Size::default(), "hello", ()).unwrap();
+    program.run::<App>(WindowSettings::new("hello"), ()).unwrap();
 }
 
 struct App {}
@@ -22,7 +22,7 @@ impl Model for App {
         (Self {}, Command::None)
     }
 
-    fn subscriptions() -> Subscriptions<Self::Message> {
+    fn subscriptions(&self) -> Subscriptions<Self::Message> {
         Subscriptions::default()
     }
 
@@ -3,7 +3,7 @@ use std::iter::zip;
 use rand::prelude::*;
 use show::{
     graphics::{Color, Context, DrawMode, PointArray, PointColorArray, Shape, VertexArray},
-    Action, Bounds, Drawer, Event, Length, MouseButton, Point, Program, Size, View,
+    Bounds, Drawer, Event, Length, MouseButton, Point, Program, Status, View, WindowSettings,
 };
 
 const DT: f32 = 0.001; // шаг времени
@@ -69,6 +69,30 @@ impl View for Simulator {
             axes: PointArray::new(context),
         })
     }
+
+    fn reconcile(
+        &self,
+        context: &Context,
+        drawer: &mut Box<dyn Drawer<()>>,
+        messages: &mut Vec<()>,
+    ) -> bool {
+        match drawer
+            .as_mut()
+            .as_any_mut()
+            .downcast_mut::<SimulatorDrawer>()
+        {
+            Some(simulator) => {
+                simulator.velocity = self.velocity;
+                simulator.max_y = self.max_y;
+                false
+            }
+            None => {
+                drawer.blur(messages);
+                *drawer = self.new_drawer(context);
+                true
+            }
+        }
+    }
 }
 
 fn fade_fn(s: f32, t: f32) -> f32 {
@@ -118,6 +142,10 @@ impl Drawer for SimulatorDrawer {
         Length::Fill
     }
 
+    fn bounds(&self) -> Bounds {
+        self.bounds
+    }
+
     fn set_bounds(&mut self, context: &Context, bounds: Bounds) {
         self.bounds = bounds;
         self.size = bounds.size().to_f32();
@@ -134,26 +162,23 @@ impl Drawer for SimulatorDrawer {
         )
     }
 
-    fn process(&mut self, event: Event) -> Option<()> {
+    fn process(&mut self, event: Event, _messages: &mut Vec<()>) -> Status {
         match event {
-            event => match event {
-                Event::CursorPos(x, y) => {
-                    self.p0 = Point::new(2. * x as f32 - self.size.x, self.size.y - 2. * y as f32)
-                        .mul(self.max_y / self.size.y);
-                }
-                Event::MouseButton(button, action, _modifiers) => {
-                    if button == MouseButton::Button1 {
-                        match action {
-                            Action::Press => self.pressed = true,
-                            Action::Release => self.pressed = false,
-                            _ => {}
-                        }
-                    }
-                }
-                _ => {}
-            },
+            Event::PointerMoved(p) => {
+                self.p0 = Point::new(2. * p.x as f32 - self.size.x, 2. * p.y as f32 - self.size.y)
+                    .mul(self.max_y / self.size.y);
+            }
+            Event::PointerPressed {
+                button: MouseButton::Left,
+                ..
+            } => self.pressed = true,
+            Event::PointerReleased {
+                button: MouseButton::Left,
+                ..
+            } => self.pressed = false,
+            _ => return Status::Ignored,
         }
-        None
+        Status::Handled
     }
 
     fn draw(&mut self, context: &Context) {
@@ -206,9 +231,12 @@ impl Drawer for SimulatorDrawer {
 fn main() {
     let mut program = Program::new().unwrap();
     program
-        .show(Size::Max, "Differential equations", || Simulator {
-            velocity: |p| Point::new(2. * p.x - 1. * p.y, 6. * p.x - 1. * p.y),
-            max_y: 1.,
-        })
+        .show(
+            WindowSettings::new("Differential equations").maximized(true),
+            || Simulator {
+                velocity: |p| Point::new(2. * p.x - 1. * p.y, 6. * p.x - 1. * p.y),
+                max_y: 1.,
+            },
+        )
         .unwrap();
 }
@@ -1,6 +1,38 @@
+use crate::{Key, WindowCommand};
+
 pub enum Command<M> {
-    Phantom(M),
+    /// Feeds the message back to `Model::update` right after the current update.
+    Message(M),
+    /// Executes the commands in order.
+    Batch(Vec<Command<M>>),
+    /// Runs the task on a worker thread and feeds its result to `Model::update`.
+    Perform(Box<dyn FnOnce() -> M + Send>),
+    /// Moves keyboard focus to the drawer with the given id, or into the keyed child with
+    /// the given key, after the view is rebuilt.
+    Focus(Key),
+    /// Puts the text on the system clipboard.
+    SetClipboard(String),
+    /// Feeds the text on the system clipboard, if any, to `Model::update`.
+    ReadClipboard(Box<dyn FnOnce(Option<String>) -> M>),
+    /// Changes the title, size, position or state of the window.
+    Window(WindowCommand),
+    /// Rebuilds the view from the updated model.
     Update,
+    /// Closes the window and returns from `Program::run`.
     Terminate,
     None,
 }
+
+impl<M> Command<M> {
+    /// Runs `task` off the UI thread and maps its output to a message.
+    pub fn perform<T>(
+        task: impl FnOnce() -> T + Send + 'static,
+        map: impl FnOnce(T) -> M + Send + 'static,
+    ) -> Self {
+        Self::Perform(Box::new(move || map(task())))
+    }
+
+    pub fn read_clipboard(map: impl FnOnce(Option<String>) -> M + 'static) -> Self {
+        Self::ReadClipboard(Box::new(map))
+    }
+}
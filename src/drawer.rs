@@ -1,12 +1,45 @@
-use std::rc::Rc;
+use std::any::Any;
 
-use crate::{graphics::Context, Bounds, Event, Length, Orientation, Point, Style};
+use crate::{
+    graphics::Context, Bounds, Event, Key, Length, MouseButton, Orientation, Point, Style, View,
+};
 
-pub trait Drawer<M = ()> {
+/// Gives access to the concrete type behind a `dyn Drawer`, so that views can reuse their drawers.
+pub trait AsAny {
+    fn as_any_mut(&mut self) -> &mut dyn Any;
+}
+
+impl<T: Any> AsAny for T {
+    fn as_any_mut(&mut self) -> &mut dyn Any {
+        self
+    }
+}
+
+/// Tells the parent of a drawer whether the event was consumed.
+/// Ignored events bubble up to the parent, which may handle them itself.
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum Status {
+    Ignored,
+    Handled,
+}
+
+pub trait Drawer<M = ()>: AsAny {
     fn width(&self) -> Length;
     fn height(&self) -> Length;
+    fn bounds(&self) -> Bounds;
     fn set_bounds(&mut self, context: &Context, bounds: Bounds);
-    fn process(&mut self, event: Event) -> Option<M>;
+    /// Pointer events carry coordinates relative to `bounds().min`, in framebuffer pixels
+    /// with y pointing up. `Event::PointerEntered` and `Event::PointerLeft` are sent when
+    /// the pointer crosses the bounds.
+    ///
+    /// A drawer that handles a mouse press captures the pointer. It keeps receiving cursor
+    /// and button events, even outside its bounds, until that button is released.
+    ///
+    /// Clicks, long presses and drags are recognized from the pointer events and delivered
+    /// as events of their own.
+    ///
+    /// Messages pushed to `messages` are fed to `Model::update` in order.
+    fn process(&mut self, event: Event, messages: &mut Vec<M>) -> Status;
     fn draw(&mut self, context: &Context);
 
     fn adjust_bounds(
@@ -25,6 +58,61 @@ pub trait Drawer<M = ()> {
         self.set_bounds(context, bounds);
         bounds.max
     }
+
+    /// Whether the drawer takes keyboard focus. Focusable drawers receive `Event::FocusGained`
+    /// and `Event::FocusLost`, and key events while they hold focus.
+    fn focusable(&self) -> bool {
+        false
+    }
+
+    /// Whether a drawer within this one holds focus.
+    fn contains_focus(&self) -> bool {
+        false
+    }
+
+    /// Identifies the drawer for `Command::Focus`. Children of keyed containers are also
+    /// found by their key.
+    fn id(&self) -> Option<Key> {
+        None
+    }
+
+    /// Focuses the first focusable drawer within this one, or the last one going backwards.
+    fn focus_first(&mut self, _backwards: bool, messages: &mut Vec<M>) -> bool {
+        if self.focusable() {
+            self.process(Event::FocusGained, messages);
+        }
+        self.focusable()
+    }
+
+    /// Moves focus past the focused drawer within this one. Returns `false` once focus
+    /// leaves the drawer.
+    fn focus_next(&mut self, _backwards: bool, messages: &mut Vec<M>) -> bool {
+        self.blur(messages);
+        false
+    }
+
+    /// Focuses the drawer with the given id within this one.
+    fn focus_id(&mut self, id: Key, messages: &mut Vec<M>) -> bool {
+        let found = self.focusable() && self.id() == Some(id);
+        if found {
+            self.process(Event::FocusGained, messages);
+        }
+        found
+    }
+
+    /// Removes focus from the drawer and everything within it.
+    fn blur(&mut self, messages: &mut Vec<M>) {
+        if self.focusable() {
+            self.process(Event::FocusLost, messages);
+        }
+    }
+}
+
+/// Moves focus to the next focusable drawer in the tree, wrapping around at either end.
+pub(crate) fn cycle_focus<M>(drawer: &mut dyn Drawer<M>, backwards: bool, messages: &mut Vec<M>) {
+    if !drawer.focus_next(backwards, messages) {
+        drawer.focus_first(backwards, messages);
+    }
 }
 
 pub struct CommonDrawer<M> {
@@ -32,29 +120,169 @@ pub struct CommonDrawer<M> {
     pub style: Style,
     pub orientation: Orientation,
     pub children: Vec<Box<dyn Drawer<M>>>,
+    pub keys: Vec<Option<Key>>,
+    hovered: Option<usize>,
+    /// The child that captured the pointer and the button that it handled
+    captured: Option<(usize, MouseButton)>,
+    focused: Option<usize>,
 }
 
 impl<M> CommonDrawer<M> {
-    fn count_child_portions_x(&self) -> f64 {
-        Length::count_portions(self.children.iter().map(|child| child.width()))
+    pub fn new(
+        orientation: Orientation,
+        style: Style,
+        children: Vec<Box<dyn Drawer<M>>>,
+        keys: Vec<Option<Key>>,
+    ) -> Self {
+        Self {
+            bounds: Bounds::zero(),
+            style,
+            orientation,
+            children,
+            keys,
+            hovered: None,
+            captured: None,
+            focused: None,
+        }
     }
 
-    fn count_child_portions_y(&self) -> f64 {
-        Length::count_portions(self.children.iter().map(|child| child.height()))
+    /// Matches the children against new views, reusing the drawers of keyed children by key
+    /// and of the rest by their order among unkeyed siblings. Returns `true` if the children
+    /// have to be laid out again. A focused child that is discarded or rebuilt loses focus.
+    pub fn reconcile_children(
+        &mut self,
+        context: &Context,
+        views: &[Box<dyn View<M>>],
+        keys: &[Option<Key>],
+        messages: &mut Vec<M>,
+    ) -> bool {
+        self.rematch(keys, messages, |i, child, messages| match child {
+            Some(mut child) => {
+                let relayout = views[i].reconcile(context, &mut child, messages);
+                (child, relayout)
+            }
+            None => (views[i].new_drawer(context), true),
+        })
     }
-}
 
-impl<M> Drawer<M> for CommonDrawer<M> {
-    fn width(&self) -> Length {
-        self.style.width
+    /// Rebuilds the children for new keys with `update`, which receives the index of each
+    /// new child and the drawer matched to it, and returns the child's drawer and whether
+    /// it has to be laid out again.
+    fn rematch(
+        &mut self,
+        keys: &[Option<Key>],
+        messages: &mut Vec<M>,
+        mut update: impl FnMut(
+            usize,
+            Option<Box<dyn Drawer<M>>>,
+            &mut Vec<M>,
+        ) -> (Box<dyn Drawer<M>>, bool),
+    ) -> bool {
+        let mut relayout = self.keys != keys;
+        let mut old: Vec<_> = self.children.drain(..).map(Some).collect();
+        let matches = match_keys(&self.keys, keys);
+        let mut focused = None;
+        for (index, &i) in matches.iter().enumerate() {
+            let (child, changed) = match i.and_then(|i| old[i].take()) {
+                Some(child) if i == self.focused => {
+                    let previous: *const dyn Drawer<M> = child.as_ref();
+                    let (child, changed) = update(index, Some(child), messages);
+                    // a rebuilt drawer was blurred by `reconcile` and has no focus to keep
+                    if std::ptr::addr_eq(previous, child.as_ref()) {
+                        focused = Some(index);
+                    }
+                    (child, changed)
+                }
+                // drawers without focus have none to lose
+                child => update(index, child, &mut Vec::new()),
+            };
+            relayout |= changed;
+            self.children.push(child);
+        }
+        if let Some(Some(child)) = self.focused.map(|i| &mut old[i]) {
+            child.blur(messages);
+        }
+        self.focused = focused;
+        self.keys = keys.to_vec();
+        let moved =
+            |old: Option<usize>| old.and_then(|old| matches.iter().position(|&i| i == Some(old)));
+        self.hovered = moved(self.hovered);
+        self.captured = self
+            .captured
+            .and_then(|(i, button)| Some((moved(Some(i))?, button)));
+        relayout
     }
 
-    fn height(&self) -> Length {
-        self.style.height
+    fn capturing(&self) -> Option<usize> {
+        self.captured.map(|(i, _)| i)
     }
 
-    fn set_bounds(&mut self, context: &Context, bounds: Bounds) {
-        self.bounds = self.style.margin.shrink(bounds);
+    fn child_at(&mut self, position: Point<f64>) -> Option<usize> {
+        let point =
+            self.bounds.min + Point::new(position.x.floor() as i32, position.y.floor() as i32);
+        self.children
+            .iter()
+            .rposition(|child| child.bounds().contains(point))
+    }
+
+    /// Passes the event to the child, translating pointer coordinates into its local space.
+    fn forward(&mut self, child: Option<usize>, event: Event, messages: &mut Vec<M>) -> Status {
+        let origin = self.bounds.min;
+        let child = match child.and_then(|i| self.children.get_mut(i)) {
+            Some(child) => child,
+            None => return Status::Ignored,
+        };
+        let offset = origin - child.bounds().min;
+        let event = event.translate(Point::new(offset.x as f64, offset.y as f64));
+        child.process(event, messages)
+    }
+
+    /// Forwards a press to the child. Focus moves to the child if it, or a drawer within it,
+    /// takes focus, and is removed from the previously focused child otherwise.
+    fn press(&mut self, child: Option<usize>, event: Event, messages: &mut Vec<M>) -> Status {
+        // the previous focus is removed before anything the press causes is announced
+        let mut pressed = Vec::new();
+        if let Some(i) = child.filter(|&i| child != self.focused && self.children[i].focusable()) {
+            self.children[i].process(Event::FocusGained, &mut pressed);
+        }
+        let status = self.forward(child, event, &mut pressed);
+        let focused = child.filter(|&i| {
+            let child = &self.children[i];
+            child.focusable() || child.contains_focus()
+        });
+        if focused != self.focused {
+            if let Some(previous) = self.focused {
+                self.children[previous].blur(messages);
+            }
+            self.focused = focused;
+        }
+        messages.append(&mut pressed);
+        status
+    }
+
+    /// Focuses the first child after `start` that takes focus.
+    fn focus_from(&mut self, start: usize, backwards: bool, messages: &mut Vec<M>) -> bool {
+        let order: Vec<usize> = if backwards {
+            (0..start).rev().collect()
+        } else {
+            (start..self.children.len()).collect()
+        };
+        self.focused = order
+            .into_iter()
+            .find(|&i| self.children[i].focus_first(backwards, messages));
+        self.focused.is_some()
+    }
+
+    fn hover(&mut self, child: Option<usize>, messages: &mut Vec<M>) {
+        if child != self.hovered {
+            self.forward(self.hovered, Event::PointerLeft, messages);
+            self.forward(child, Event::PointerEntered, messages);
+            self.hovered = child;
+        }
+    }
+
+    /// Lays out the children inside the current bounds.
+    pub fn layout(&mut self, context: &Context) {
         let bounds = self.style.padding.shrink(self.bounds);
         let parent_min = bounds.min;
         let parent_size = bounds.size();
@@ -90,8 +318,156 @@ impl<M> Drawer<M> for CommonDrawer<M> {
         }
     }
 
-    fn process(&mut self, _event: Event) -> Option<M> {
-        None
+    fn count_child_portions_x(&self) -> f64 {
+        Length::count_portions(self.children.iter().map(|child| child.width()))
+    }
+
+    fn count_child_portions_y(&self) -> f64 {
+        Length::count_portions(self.children.iter().map(|child| child.height()))
+    }
+}
+
+/// For each new key, the index of the old child whose drawer it reuses: the child with
+/// the same key, or for unkeyed children the next unkeyed old child.
+fn match_keys(old: &[Option<Key>], new: &[Option<Key>]) -> Vec<Option<usize>> {
+    let mut unkeyed = old
+        .iter()
+        .enumerate()
+        .filter(|(_, key)| key.is_none())
+        .map(|(i, _)| i);
+    new.iter()
+        .map(|key| match key {
+            Some(key) => old.iter().position(|k| k == &Some(*key)),
+            None => unkeyed.next(),
+        })
+        .collect()
+}
+
+impl<M: 'static> Drawer<M> for CommonDrawer<M> {
+    fn width(&self) -> Length {
+        self.style.width
+    }
+
+    fn height(&self) -> Length {
+        self.style.height
+    }
+
+    fn bounds(&self) -> Bounds {
+        self.bounds
+    }
+
+    fn set_bounds(&mut self, context: &Context, bounds: Bounds) {
+        self.bounds = self.style.margin.shrink(bounds);
+        self.layout(context);
+    }
+
+    fn process(&mut self, event: Event, messages: &mut Vec<M>) -> Status {
+        // pointer events go to the topmost child under the cursor, or to the child that
+        // captured the pointer by handling a press, keyboard events to the focused child
+        let target = match &event {
+            Event::PointerMoved(position) => {
+                if self.captured.is_none() {
+                    let hovered = self.child_at(*position);
+                    self.hover(hovered, messages);
+                }
+                self.capturing().or(self.hovered)
+            }
+            Event::PointerLeft => {
+                if self.captured.is_none() {
+                    self.hover(None, messages);
+                }
+                return Status::Ignored;
+            }
+            Event::PointerPressed {
+                position, button, ..
+            } => {
+                let button = *button;
+                let target = self.capturing().or_else(|| self.child_at(*position));
+                let status = self.press(target, event, messages);
+                if status == Status::Handled && self.captured.is_none() {
+                    self.captured = target.map(|i| (i, button));
+                }
+                return status;
+            }
+            Event::PointerReleased {
+                position, button, ..
+            } => {
+                let (position, button) = (*position, *button);
+                let target = self.capturing().or_else(|| self.child_at(position));
+                let status = self.forward(target, event, messages);
+                // capture ends with the button that started it, which always reaches this
+                // drawer because every container above it captured the same press
+                if self
+                    .captured
+                    .is_some_and(|(_, captured)| captured == button)
+                {
+                    self.captured = None;
+                    let hovered = self.child_at(position);
+                    self.hover(hovered, messages);
+                }
+                return status;
+            }
+            Event::Clicked { position, .. }
+            | Event::LongPressed { position, .. }
+            | Event::DragStarted { position, .. }
+            | Event::Dragged { position, .. }
+            | Event::DragEnded { position, .. }
+            | Event::Scrolled { position, .. } => {
+                self.capturing().or_else(|| self.child_at(*position))
+            }
+            Event::FilesDropped { position, .. } => self.child_at(*position),
+            Event::KeyPressed { .. } | Event::KeyReleased { .. } | Event::Text(_) => self.focused,
+            _ => None,
+        };
+        self.forward(target, event, messages)
+    }
+
+    fn contains_focus(&self) -> bool {
+        self.focused.is_some()
+    }
+
+    fn focus_first(&mut self, backwards: bool, messages: &mut Vec<M>) -> bool {
+        let start = if backwards { self.children.len() } else { 0 };
+        self.focus_from(start, backwards, messages)
+    }
+
+    fn focus_next(&mut self, backwards: bool, messages: &mut Vec<M>) -> bool {
+        let focused = match self.focused {
+            Some(focused) => focused,
+            None => return self.focus_first(backwards, messages),
+        };
+        if self.children[focused].focus_next(backwards, messages) {
+            return true;
+        }
+        let start = if backwards { focused } else { focused + 1 };
+        self.focus_from(start, backwards, messages)
+    }
+
+    fn focus_id(&mut self, id: Key, messages: &mut Vec<M>) -> bool {
+        // the previous focus is removed before the new one is announced
+        let mut gained = Vec::new();
+        let found = (0..self.children.len()).find(|&i| {
+            // a keyed child is focused by its key, at its first focusable drawer
+            if self.keys.get(i) != Some(&Some(id)) {
+                self.children[i].focus_id(id, &mut gained)
+            } else {
+                self.focused == Some(i) || self.children[i].focus_first(false, &mut gained)
+            }
+        });
+        if let Some(found) = found {
+            if let Some(focused) = self.focused.filter(|&focused| focused != found) {
+                self.children[focused].blur(messages);
+            }
+            self.focused = Some(found);
+            messages.append(&mut gained);
+        }
+        found.is_some()
+    }
+
+    fn blur(&mut self, messages: &mut Vec<M>) {
+        if let Some(focused) = self.focused.take() {
+            self.children[focused].blur(messages);
+        }
     }
 
     fn draw(&mut self, context: &Context) {
@@ -101,3 +477,290 @@ impl<M> Drawer<M> for CommonDrawer<M> {
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::Modifiers;
+
+    type Log = Vec<(&'static str, Event)>;
+
+    /// A fixed-size drawer that records its events and handles presses if `handles`.
+    struct Leaf {
+        name: &'static str,
+        bounds: Bounds,
+        handles: bool,
+        focusable: bool,
+    }
+
+    impl Drawer<(&'static str, Event)> for Leaf {
+        fn width(&self) -> Length {
+            Length::Fill
+        }
+
+        fn height(&self) -> Length {
+            Length::Fill
+        }
+
+        fn bounds(&self) -> Bounds {
+            self.bounds
+        }
+
+        fn set_bounds(&mut self, _context: &Context, bounds: Bounds) {
+            self.bounds = bounds;
+        }
+
+        fn process(&mut self, event: Event, messages: &mut Log) -> Status {
+            let handled = self.handles && matches!(event, Event::PointerPressed { .. });
+            messages.push((self.name, event));
+            if handled {
+                Status::Handled
+            } else {
+                Status::Ignored
+            }
+        }
+
+        fn draw(&mut self, _context: &Context) {}
+
+        fn focusable(&self) -> bool {
+            self.focusable
+        }
+    }
+
+    fn leaf(name: &'static str, x: i32, handles: bool) -> Box<dyn Drawer<(&'static str, Event)>> {
+        Box::new(Leaf {
+            name,
+            bounds: Bounds::new(x, 0, x + 100, 100),
+            handles,
+            focusable: false,
+        })
+    }
+
+    fn focusable(name: &'static str, x: i32) -> Box<dyn Drawer<(&'static str, Event)>> {
+        Box::new(Leaf {
+            name,
+            bounds: Bounds::new(x, 0, x + 100, 100),
+            handles: false,
+            focusable: true,
+        })
+    }
+
+    /// Takes the focus changes from the log, as `true` for gained and `false` for lost.
+    fn focus_changes(messages: &mut Log) -> Vec<(&'static str, bool)> {
+        let changes = messages.drain(..).filter_map(|(name, event)| match event {
+            Event::FocusGained => Some((name, true)),
+            Event::FocusLost => Some((name, false)),
+            _ => None,
+        });
+        changes.collect()
+    }
+
+    fn row(
+        x: i32,
+        children: Vec<Box<dyn Drawer<(&'static str, Event)>>>,
+    ) -> CommonDrawer<(&'static str, Event)> {
+        let keys = vec![None; children.len()];
+        let width = 100 * children.len() as i32;
+        let mut row = CommonDrawer::new(Orientation::Horizontal, Style::default(), children, keys);
+        row.bounds = Bounds::new(x, 0, x + width, 100);
+        row
+    }
+
+    fn click(drawer: &mut dyn Drawer<(&'static str, Event)>, x: f64, messages: &mut Log) {
+        let position = Point::new(x, 50.0);
+        let (button, modifiers) = (MouseButton::Left, Modifiers::default());
+        drawer.process(Event::PointerMoved(position), messages);
+        drawer.process(
+            Event::PointerPressed {
+                position,
+                button,
+                modifiers,
+            },
+            messages,
+        );
+        drawer.process(
+            Event::PointerReleased {
+                position,
+                button,
+                modifiers,
+            },
+            messages,
+        );
+    }
+
+    fn pressed(messages: &Log) -> Vec<&'static str> {
+        let pressed = messages
+            .iter()
+            .filter(|(_, event)| matches!(event, Event::PointerPressed { .. }));
+        pressed.map(|(name, _)| *name).collect()
+    }
+
+    #[test]
+    fn nested_capture_ends_with_its_button() {
+        let inner = row(0, vec![leaf("b1", 0, false), leaf("b2", 100, true)]);
+        let mut root = row(0, vec![Box::new(inner), leaf("c", 200, true)]);
+        let mut messages = Vec::new();
+        let (button, modifiers) = (MouseButton::Left, Modifiers::default());
+        // an ignored press on b1 released over c
+        let position = Point::new(50.0, 50.0);
+        root.process(Event::PointerMoved(position), &mut messages);
+        root.process(
+            Event::PointerPressed {
+                position,
+                button,
+                modifiers,
+            },
+            &mut messages,
+        );
+        let position = Point::new(250.0, 50.0);
+        root.process(Event::PointerMoved(position), &mut messages);
+        root.process(
+            Event::PointerReleased {
+                position,
+                button,
+                modifiers,
+            },
+            &mut messages,
+        );
+        click(&mut root, 150.0, &mut messages);
+        click(&mut root, 50.0, &mut messages);
+        assert_eq!(pressed(&messages), ["b1", "b2", "b1"]);
+    }
+
+    #[test]
+    fn capture_outlasts_other_buttons() {
+        let mut root = row(0, vec![leaf("a", 0, true), leaf("b", 100, true)]);
+        let mut messages = Vec::new();
+        let modifiers = Modifiers::default();
+        let position = Point::new(50.0, 50.0);
+        let (left, right) = (MouseButton::Left, MouseButton::Right);
+        root.process(
+            Event::PointerPressed {
+                position,
+                button: left,
+                modifiers,
+            },
+            &mut messages,
+        );
+        let position = Point::new(150.0, 50.0);
+        root.process(
+            Event::PointerPressed {
+                position,
+                button: right,
+                modifiers,
+            },
+            &mut messages,
+        );
+        root.process(
+            Event::PointerReleased {
+                position,
+                button: right,
+                modifiers,
+            },
+            &mut messages,
+        );
+        root.process(Event::PointerMoved(position), &mut messages);
+        assert!(messages.iter().all(|(name, _)| *name == "a"));
+        root.process(
+            Event::PointerReleased {
+                position,
+                button: left,
+                modifiers,
+            },
+            &mut messages,
+        );
+        root.process(Event::PointerMoved(position), &mut messages);
+        assert_eq!(messages.last().map(|(name, _)| *name), Some("b"));
+    }
+
+    #[test]
+    fn tab_cycles_focus_and_wraps_around() {
+        let inner = row(200, vec![focusable("c", 200)]);
+        let children = vec![
+            focusable("a", 0),
+            leaf("b", 100, true),
+            Box::new(inner) as _,
+        ];
+        let mut root = row(0, children);
+        let mut messages = Vec::new();
+        cycle_focus(&mut root, false, &mut messages);
+        assert_eq!(focus_changes(&mut messages), [("a", true)]);
+        cycle_focus(&mut root, false, &mut messages);
+        assert_eq!(focus_changes(&mut messages), [("a", false), ("c", true)]);
+        cycle_focus(&mut root, false, &mut messages);
+        assert_eq!(focus_changes(&mut messages), [("c", false), ("a", true)]);
+        cycle_focus(&mut root, true, &mut messages);
+        assert_eq!(focus_changes(&mut messages), [("a", false), ("c", true)]);
+        cycle_focus(&mut root, true, &mut messages);
+        assert_eq!(focus_changes(&mut messages), [("c", false), ("a", true)]);
+    }
+
+    #[test]
+    fn clicks_move_focus() {
+        let mut root = row(0, vec![focusable("a", 0), leaf("b", 100, true)]);
+        let mut messages = Vec::new();
+        click(&mut root, 50.0, &mut messages);
+        assert_eq!(focus_changes(&mut messages), [("a", true)]);
+        click(&mut root, 50.0, &mut messages);
+        assert_eq!(focus_changes(&mut messages), []);
+        click(&mut root, 150.0, &mut messages);
+        assert_eq!(focus_changes(&mut messages), [("a", false)]);
+        assert!(!root.contains_focus());
+    }
+
+    #[test]
+    fn keyed_children_take_focus_by_key() {
+        let inner = row(100, vec![leaf("b", 100, true), focusable("c", 200)]);
+        let mut root = row(0, vec![focusable("a", 0), Box::new(inner)]);
+        root.keys = keys(&[Some(1), Some(2)]);
+        let mut messages = Vec::new();
+        assert!(root.focus_id(Key::new(1), &mut messages));
+        assert_eq!(focus_changes(&mut messages), [("a", true)]);
+        assert!(root.focus_id(Key::new(2), &mut messages));
+        assert_eq!(focus_changes(&mut messages), [("a", false), ("c", true)]);
+        assert!(!root.focus_id(Key::new(3), &mut messages));
+        assert_eq!(focus_changes(&mut messages), []);
+    }
+
+    #[test]
+    fn discarded_focused_child_is_blurred() {
+        let mut root = row(0, vec![focusable("a", 0), focusable("b", 100)]);
+        root.keys = keys(&[Some(1), Some(2)]);
+        let mut messages = Vec::new();
+        let reuse = |_, child: Option<_>, _: &mut Log| (child.expect("no new children"), false);
+        root.focus_id(Key::new(1), &mut messages);
+        focus_changes(&mut messages);
+        // reordered, the focused child keeps focus
+        root.rematch(&keys(&[Some(2), Some(1)]), &mut messages, reuse);
+        assert_eq!(focus_changes(&mut messages), []);
+        assert!(root.contains_focus());
+        root.rematch(&keys(&[Some(2)]), &mut messages, reuse);
+        assert_eq!(focus_changes(&mut messages), [("a", false)]);
+        assert!(!root.contains_focus());
+    }
+
+    fn keys(keys: &[Option<u32>]) -> Vec<Option<Key>> {
+        keys.iter().map(|key| key.map(Key::new)).collect()
+    }
+
+    #[test]
+    fn keyed_children_keep_their_drawers() {
+        let old = keys(&[Some(1), Some(2), Some(3)]);
+        // reordered
+        let new = keys(&[Some(3), Some(1), Some(2)]);
+        assert_eq!(match_keys(&old, &new), [Some(2), Some(0), Some(1)]);
+        // inserted
+        let new = keys(&[Some(1), Some(4), Some(2), Some(3)]);
+        assert_eq!(match_keys(&old, &new), [Some(0), None, Some(1), Some(2)]);
+        // removed
+        let new = keys(&[Some(1), Some(3)]);
+        assert_eq!(match_keys(&old, &new), [Some(0), Some(2)]);
+    }
+
+    #[test]
+    fn unkeyed_children_match_in_order() {
+        let old = keys(&[None, Some(1), None]);
+        let new = keys(&[Some(1), None, None, None]);
+        assert_eq!(match_keys(&old, &new), [Some(1), Some(0), Some(2), None]);
+    }
+}
@@ -1,35 +1,636 @@
-pub type MouseButton = glfw::MouseButton;
-pub type Action = glfw::Action;
-pub type Event = glfw::WindowEvent;
+use std::{
+    collections::hash_map::DefaultHasher,
+    hash::{Hash, Hasher},
+    path::PathBuf,
+    rc::Rc,
+    sync::{mpsc, Arc, Weak},
+    thread,
+    time::{Duration, Instant},
+};
+
+use crate::{watch::Watcher, GamepadEvent, KeyCode, Point, Shortcut, ShortcutError, Shortcuts};
+
+#[derive(Clone, Copy, PartialEq, Eq, Hash, Debug)]
+pub enum MouseButton {
+    Left,
+    Right,
+    Middle,
+    Other(u8),
+}
+
+impl From<glfw::MouseButton> for MouseButton {
+    fn from(button: glfw::MouseButton) -> Self {
+        match button {
+            glfw::MouseButtonLeft => Self::Left,
+            glfw::MouseButtonRight => Self::Right,
+            glfw::MouseButtonMiddle => Self::Middle,
+            button => Self::Other(button as u8),
+        }
+    }
+}
+
+/// Modifier keys held during an event. `logo` is the Windows, Command or Super key.
+#[derive(Clone, Copy, PartialEq, Eq, Hash, Debug, Default)]
+pub struct Modifiers {
+    pub shift: bool,
+    pub control: bool,
+    pub alt: bool,
+    pub logo: bool,
+}
+
+impl From<glfw::Modifiers> for Modifiers {
+    fn from(modifiers: glfw::Modifiers) -> Self {
+        Self {
+            shift: modifiers.contains(glfw::Modifiers::Shift),
+            control: modifiers.contains(glfw::Modifiers::Control),
+            alt: modifiers.contains(glfw::Modifiers::Alt),
+            logo: modifiers.contains(glfw::Modifiers::Super),
+        }
+    }
+}
+
+/// Scroll amount, positive when scrolling up or to the right. Mouse wheels scroll by
+/// whole lines, trackpads by fractions of a line; `pixels` is the same amount in
+/// framebuffer pixels.
+#[derive(Clone, Copy, PartialEq, Debug)]
+pub struct ScrollDelta {
+    pub lines: Point<f64>,
+    pub pixels: Point<f64>,
+}
+
+/// Positions are in framebuffer pixels with y pointing up. Subscriptions receive them
+/// relative to the window, drawers relative to their bounds.
+#[derive(Clone, PartialEq, Debug)]
+pub enum Event {
+    PointerMoved(Point<f64>),
+    PointerEntered,
+    PointerLeft,
+    PointerPressed {
+        position: Point<f64>,
+        button: MouseButton,
+        modifiers: Modifiers,
+    },
+    PointerReleased {
+        position: Point<f64>,
+        button: MouseButton,
+        modifiers: Modifiers,
+    },
+    /// A press and release of a button without dragging, sent just before `PointerReleased`.
+    /// `count` is 2 for a double click, 3 for a triple click and so on.
+    Clicked {
+        position: Point<f64>,
+        button: MouseButton,
+        count: u32,
+        modifiers: Modifiers,
+    },
+    /// A button was held without moving the pointer. No click follows the release.
+    LongPressed {
+        position: Point<f64>,
+        button: MouseButton,
+    },
+    /// The pointer moved past the drag threshold while a button was held. `position` is
+    /// where the button was pressed.
+    DragStarted {
+        position: Point<f64>,
+        button: MouseButton,
+    },
+    /// The pointer moved during a drag. `offset` is measured from where the drag started.
+    Dragged {
+        position: Point<f64>,
+        offset: Point<f64>,
+        button: MouseButton,
+    },
+    /// The button of a drag was released, just before `PointerReleased`.
+    DragEnded {
+        position: Point<f64>,
+        offset: Point<f64>,
+        button: MouseButton,
+    },
+    KeyPressed {
+        key: KeyCode,
+        modifiers: Modifiers,
+        repeat: bool,
+    },
+    KeyReleased {
+        key: KeyCode,
+        modifiers: Modifiers,
+    },
+    Text(char),
+    Scrolled {
+        position: Point<f64>,
+        delta: ScrollDelta,
+        modifiers: Modifiers,
+    },
+    /// Files were dragged from another application and dropped onto the window.
+    /// Delivered to the drawer under the pointer.
+    FilesDropped {
+        position: Point<f64>,
+        paths: Vec<PathBuf>,
+    },
+    /// The framebuffer was resized.
+    Resized(Point),
+    /// The window moved to the position in screen coordinates.
+    Moved(Point),
+    /// The window gained or lost the keyboard focus of the system.
+    WindowFocused(bool),
+    /// The window was minimized, or restored with `false`.
+    Iconified(bool),
+    /// The ratio between framebuffer pixels and screen coordinates changed, e.g. because
+    /// the window moved to another monitor.
+    ScaleChanged(Point<f32>),
+    /// The user tried to close the window. The window closes unless the model subscribed
+    /// with `Subscriptions::on_close_request`.
+    CloseRequested,
+    /// A drawer gained or lost keyboard focus.
+    FocusGained,
+    FocusLost,
+}
+
+impl Event {
+    /// Converts a glfw event, taking the pointer position for events that lack one
+    /// from `cursor`, in framebuffer coordinates with y pointing up. Events that carry
+    /// window coordinates, like `CursorPos`, are converted by the runtime, which knows
+    /// the scale of the window, and yield `None` here.
+    pub(crate) fn from_glfw(event: glfw::WindowEvent, cursor: Point<f64>) -> Option<Self> {
+        use glfw::{Action, WindowEvent};
+        Some(match event {
+            WindowEvent::CursorEnter(true) => Self::PointerEntered,
+            WindowEvent::CursorEnter(false) => Self::PointerLeft,
+            WindowEvent::MouseButton(button, Action::Release, modifiers) => Self::PointerReleased {
+                position: cursor,
+                button: button.into(),
+                modifiers: modifiers.into(),
+            },
+            WindowEvent::MouseButton(button, _, modifiers) => Self::PointerPressed {
+                position: cursor,
+                button: button.into(),
+                modifiers: modifiers.into(),
+            },
+            WindowEvent::Key(key, _, Action::Release, modifiers) => Self::KeyReleased {
+                key: key.into(),
+                modifiers: modifiers.into(),
+            },
+            WindowEvent::Key(key, _, action, modifiers) => Self::KeyPressed {
+                key: key.into(),
+                modifiers: modifiers.into(),
+                repeat: action == Action::Repeat,
+            },
+            WindowEvent::Char(c) | WindowEvent::CharModifiers(c, _) => Self::Text(c),
+            WindowEvent::FramebufferSize(width, height) => Self::Resized(Point::new(width, height)),
+            WindowEvent::Pos(x, y) => Self::Moved(Point::new(x, y)),
+            WindowEvent::Focus(focused) => Self::WindowFocused(focused),
+            WindowEvent::Iconify(iconified) => Self::Iconified(iconified),
+            WindowEvent::ContentScale(x, y) => Self::ScaleChanged(Point::new(x, y)),
+            WindowEvent::Close => Self::CloseRequested,
+            _ => return None,
+        })
+    }
+
+    /// The pointer position carried by the event.
+    pub fn position(&self) -> Option<Point<f64>> {
+        match self {
+            Self::PointerMoved(position)
+            | Self::PointerPressed { position, .. }
+            | Self::PointerReleased { position, .. }
+            | Self::Clicked { position, .. }
+            | Self::LongPressed { position, .. }
+            | Self::DragStarted { position, .. }
+            | Self::Dragged { position, .. }
+            | Self::DragEnded { position, .. }
+            | Self::Scrolled { position, .. }
+            | Self::FilesDropped { position, .. } => Some(*position),
+            _ => None,
+        }
+    }
+
+    /// Moves the pointer position carried by the event by `offset`.
+    pub fn translate(mut self, offset: Point<f64>) -> Self {
+        match &mut self {
+            Self::PointerMoved(position)
+            | Self::PointerPressed { position, .. }
+            | Self::PointerReleased { position, .. }
+            | Self::Clicked { position, .. }
+            | Self::LongPressed { position, .. }
+            | Self::DragStarted { position, .. }
+            | Self::Dragged { position, .. }
+            | Self::DragEnded { position, .. }
+            | Self::Scrolled { position, .. }
+            | Self::FilesDropped { position, .. } => *position = *position + offset,
+            _ => {}
+        }
+        self
+    }
+}
+
+#[derive(Clone)]
+struct Timer<M> {
+    interval: Duration,
+    message: M,
+    next: Option<Instant>,
+}
+
+impl<M: Clone> Timer<M> {
+    fn tick(&mut self, now: Instant) -> Option<M> {
+        let next = self.next.get_or_insert(now + self.interval);
+        if *next > now {
+            return None;
+        }
+        // ticks missed while the loop was busy are dropped
+        *next += self.interval;
+        if *next <= now {
+            *next = now + self.interval;
+        }
+        Some(self.message.clone())
+    }
+}
+
+type Items<M> = Box<dyn Iterator<Item = M> + Send>;
+
+/// An iterator drained on its own thread while the subscription is active.
+struct Stream<M> {
+    id: u64,
+    /// Builds the iterator. The iterator may stop early once the handle it is given
+    /// can no longer be upgraded, which happens when the subscription is dropped.
+    start: Rc<dyn Fn(Weak<()>) -> Items<M>>,
+    running: Option<(mpsc::Receiver<M>, Arc<()>)>,
+}
+
+impl<M: Send + 'static> Stream<M> {
+    fn poll(&mut self) -> Vec<M> {
+        let start = &self.start;
+        let (receiver, _) = self.running.get_or_insert_with(|| {
+            let (sender, receiver) = mpsc::channel();
+            let alive = Arc::new(());
+            let items = start(Arc::downgrade(&alive));
+            // the thread stops when the iterator ends or at the first item produced
+            // after the subscription is dropped
+            thread::spawn(move || {
+                for item in items {
+                    if sender.send(item).is_err() {
+                        break;
+                    }
+                }
+            });
+            (receiver, alive)
+        });
+        receiver.try_iter().collect()
+    }
+}
+
+impl<M> Clone for Stream<M> {
+    fn clone(&self) -> Self {
+        Self {
+            id: self.id,
+            start: self.start.clone(),
+            running: None,
+        }
+    }
+}
 
 pub struct Subscriptions<M> {
     filters: Vec<fn(Event) -> Option<M>>,
+    timers: Vec<Timer<M>>,
+    frames: Vec<fn(Duration) -> M>,
+    channels: Vec<Rc<dyn Fn() -> Vec<M>>>,
+    streams: Vec<Stream<M>>,
+    shortcuts: Vec<(Shortcut, M)>,
+    gamepads: Vec<fn(GamepadEvent) -> M>,
+    close_requests: Vec<M>,
 }
 
 impl<M> Subscriptions<M> {
     pub fn new(filter: fn(Event) -> Option<M>) -> Self {
         Self {
             filters: vec![filter],
+            ..Self::empty()
+        }
+    }
+
+    /// Emits `message` once per `interval`.
+    pub fn every(interval: Duration, message: M) -> Self {
+        Self {
+            timers: vec![Timer {
+                interval,
+                message,
+                next: None,
+            }],
+            ..Self::empty()
+        }
+    }
+
+    /// Emits a message every frame, built from the time elapsed since the previous frame.
+    pub fn on_frame(tick: fn(Duration) -> M) -> Self {
+        Self {
+            frames: vec![tick],
+            ..Self::empty()
+        }
+    }
+
+    /// Emits the items received on the channel, polled every frame.
+    pub fn receiver<T: 'static>(receiver: Rc<mpsc::Receiver<T>>, map: fn(T) -> M) -> Self
+    where
+        M: 'static,
+    {
+        Self {
+            channels: vec![Rc::new(move || receiver.try_iter().map(map).collect())],
+            ..Self::empty()
+        }
+    }
+
+    /// Emits the items of the iterator built by `start`, which runs on its own thread
+    /// while a subscription with the same `id` stays active. Once the subscription is
+    /// dropped, the thread exits at the next item the iterator yields, so an iterator
+    /// that blocks without yielding, such as the lines of stdin, keeps its thread alive.
+    pub fn stream<I>(id: impl Hash, start: impl Fn() -> I + 'static, map: fn(I::Item) -> M) -> Self
+    where
+        I: Iterator + Send + 'static,
+        M: 'static,
+    {
+        Self::cancelable_stream(id, move |_| start(), map)
+    }
+
+    /// Like `stream`, but `start` gets a handle that can no longer be upgraded once the
+    /// subscription is dropped, so that the iterator can stop without yielding.
+    fn cancelable_stream<I>(
+        id: impl Hash,
+        start: impl Fn(Weak<()>) -> I + 'static,
+        map: fn(I::Item) -> M,
+    ) -> Self
+    where
+        I: Iterator + Send + 'static,
+        M: 'static,
+    {
+        let mut hasher = DefaultHasher::new();
+        id.hash(&mut hasher);
+        Self {
+            streams: vec![Stream {
+                id: hasher.finish(),
+                start: Rc::new(move |alive| Box::new(start(alive).map(map))),
+                running: None,
+            }],
+            ..Self::empty()
+        }
+    }
+
+    /// Emits the path of every file that is created, modified or removed at `path`,
+    /// which may be a file or a directory. Changes are detected by polling.
+    pub fn watch(path: impl Into<PathBuf>, on_change: fn(PathBuf) -> M) -> Self
+    where
+        M: 'static,
+    {
+        let path = path.into();
+        Self::cancelable_stream(
+            ("watch", path.clone()),
+            move |alive| Watcher::new(path.clone(), alive),
+            on_change,
+        )
+    }
+
+    /// Emits the bound message when a shortcut is pressed. Shortcuts are resolved before
+    /// the key event reaches the focused drawer, which then does not receive it.
+    /// Conflicts within `shortcuts` are reported by `Shortcuts::bind`, conflicts between
+    /// combined subscriptions by `try_combine`.
+    pub fn shortcuts(shortcuts: Shortcuts<M>) -> Self {
+        Self {
+            shortcuts: shortcuts.bindings,
+            ..Self::empty()
+        }
+    }
+
+    /// Emits a message for every change of the connected gamepads, which are polled
+    /// every frame. Gamepads already connected are reported when the subscription starts.
+    pub fn gamepads(on_change: fn(GamepadEvent) -> M) -> Self {
+        Self {
+            gamepads: vec![on_change],
+            ..Self::empty()
+        }
+    }
+
+    /// Emits the message when the user tries to close the window, which then stays open
+    /// until the model returns `Command::Terminate`. Without this subscription the window
+    /// closes, even if a filter turns `Event::CloseRequested` into a message.
+    pub fn on_close_request(message: M) -> Self {
+        Self {
+            close_requests: vec![message],
+            ..Self::empty()
         }
     }
 
     pub fn empty() -> Self {
-        Self { filters: vec![] }
+        Self {
+            filters: vec![],
+            timers: vec![],
+            frames: vec![],
+            channels: vec![],
+            streams: vec![],
+            shortcuts: vec![],
+            gamepads: vec![],
+            close_requests: vec![],
+        }
+    }
+
+    pub fn process(&self, event: &Event) -> Vec<M> {
+        self.filters
+            .iter()
+            .filter_map(|filter| filter(event.clone()))
+            .collect()
+    }
+
+    pub(crate) fn wants_gamepads(&self) -> bool {
+        !self.gamepads.is_empty()
+    }
+
+    pub(crate) fn process_gamepad(&self, event: &GamepadEvent) -> Vec<M> {
+        self.gamepads
+            .iter()
+            .map(|on_change| on_change(event.clone()))
+            .collect()
     }
+}
 
+impl<M: Clone> Subscriptions<M> {
+    /// Merges the subscriptions. A shortcut bound by more than one of them keeps its first
+    /// binding; use `try_combine` to have such conflicts reported.
     pub fn combine(subscriptions: &[Self]) -> Self {
+        let mut shortcuts = Shortcuts::new();
+        for (shortcut, message) in subscriptions
+            .iter()
+            .flat_map(|s| s.shortcuts.iter().cloned())
+        {
+            // later bindings of a shortcut are dropped
+            let _ = shortcuts.bind_shortcut(shortcut, message);
+        }
         Self {
             filters: subscriptions
                 .iter()
-                .map(|s| s.filters.iter().map(|&f| f))
-                .flatten()
+                .flat_map(|s| s.filters.iter().copied())
+                .collect(),
+            timers: subscriptions
+                .iter()
+                .flat_map(|s| s.timers.iter().cloned())
+                .collect(),
+            frames: subscriptions
+                .iter()
+                .flat_map(|s| s.frames.iter().copied())
+                .collect(),
+            channels: subscriptions
+                .iter()
+                .flat_map(|s| s.channels.iter().cloned())
+                .collect(),
+            streams: subscriptions
+                .iter()
+                .flat_map(|s| s.streams.iter().cloned())
                 .collect(),
+            shortcuts: shortcuts.bindings,
+            gamepads: subscriptions
+                .iter()
+                .flat_map(|s| s.gamepads.iter().copied())
+                .collect(),
+            close_requests: subscriptions
+                .iter()
+                .flat_map(|s| s.close_requests.iter().cloned())
+                .collect(),
+        }
+    }
+
+    /// Merges the subscriptions like `combine`, but fails with `ShortcutError::Conflict` if a
+    /// shortcut is bound by more than one of them.
+    pub fn try_combine(subscriptions: &[Self]) -> Result<Self, ShortcutError> {
+        let mut shortcuts = Shortcuts::new();
+        for (shortcut, _) in subscriptions.iter().flat_map(|s| &s.shortcuts) {
+            shortcuts.bind_shortcut(*shortcut, ())?;
+        }
+        Ok(Self::combine(subscriptions))
+    }
+
+    /// The message bound to the shortcut pressed by the event.
+    pub(crate) fn shortcut(&self, event: &Event) -> Option<M> {
+        self.shortcuts
+            .iter()
+            .find(|(shortcut, _)| shortcut.matches(event))
+            .map(|(_, message)| message.clone())
+    }
+
+    /// The messages that intercept a close request, empty if the window should close.
+    pub(crate) fn close_requested(&self) -> Vec<M> {
+        self.close_requests.clone()
+    }
+
+    /// Carries the state of subscriptions that stay active over from `previous`,
+    /// so that e.g. timers keep their phase across updates.
+    pub(crate) fn inherit(&mut self, previous: Self) {
+        let mut timers = previous.timers;
+        for timer in &mut self.timers {
+            if let Some(i) = timers.iter().position(|t| t.interval == timer.interval) {
+                timer.next = timers.remove(i).next;
+            }
+        }
+        let mut streams = previous.streams;
+        for stream in &mut self.streams {
+            if let Some(i) = streams.iter().position(|s| s.id == stream.id) {
+                stream.running = streams.remove(i).running;
+            }
         }
     }
 }
 
+impl<M: Clone + Send + 'static> Subscriptions<M> {
+    pub(crate) fn tick(&mut self, now: Instant, elapsed: Duration) -> Vec<M> {
+        let mut messages: Vec<M> = self.frames.iter().map(|frame| frame(elapsed)).collect();
+        messages.extend(self.timers.iter_mut().filter_map(|timer| timer.tick(now)));
+        messages.extend(self.channels.iter().flat_map(|poll| poll()));
+        messages.extend(self.streams.iter_mut().flat_map(|stream| stream.poll()));
+        messages
+    }
+}
+
 impl<M> Default for Subscriptions<M> {
     fn default() -> Self {
         Self::empty()
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    struct Signal(mpsc::Sender<()>);
+
+    impl Drop for Signal {
+        fn drop(&mut self) {
+            let _ = self.0.send(());
+        }
+    }
+
+    #[test]
+    fn watch_thread_exits_after_subscription_is_dropped() {
+        let (done, exited) = mpsc::channel();
+        let path = std::env::temp_dir().join("show-watch-test");
+        let mut stream = Stream {
+            id: 0,
+            start: Rc::new(move |alive| {
+                // dropped together with the iterator when the thread exits
+                let signal = Signal(done.clone());
+                Box::new(Watcher::new(path.clone(), alive).inspect(move |_| {
+                    let _ = &signal;
+                }))
+            }),
+            running: None,
+        };
+        assert!(stream.poll().is_empty());
+        assert!(exited.try_recv().is_err());
+        drop(stream);
+        assert!(exited.recv_timeout(Duration::from_secs(2)).is_ok());
+    }
+
+    #[test]
+    fn combine_reports_conflicting_shortcuts() {
+        let subscriptions = |message| {
+            let mut shortcuts = Shortcuts::new();
+            shortcuts.bind("Ctrl+S", message).unwrap();
+            Subscriptions::shortcuts(shortcuts)
+        };
+        let both = [subscriptions(1), subscriptions(2)];
+        let shortcut: Shortcut = "Ctrl+S".parse().unwrap();
+        assert!(matches!(
+            Subscriptions::try_combine(&both),
+            Err(ShortcutError::Conflict(conflict)) if conflict == shortcut
+        ));
+        let event = Event::KeyPressed {
+            key: shortcut.key,
+            modifiers: shortcut.modifiers,
+            repeat: false,
+        };
+        assert_eq!(Subscriptions::combine(&both).shortcut(&event), Some(1));
+        let single = Subscriptions::try_combine(&both[..1]).unwrap();
+        assert_eq!(single.shortcut(&event), Some(1));
+    }
+
+    #[test]
+    fn only_close_request_subscriptions_intercept_closing() {
+        let filter = Subscriptions::new(|event| (event == Event::CloseRequested).then_some(1));
+        assert_eq!(filter.process(&Event::CloseRequested), [1]);
+        assert!(filter.close_requested().is_empty());
+        let combined = Subscriptions::combine(&[filter, Subscriptions::on_close_request(2)]);
+        assert_eq!(combined.close_requested(), [2]);
+    }
+
+    #[test]
+    fn timer_drops_missed_ticks() {
+        let start = Instant::now();
+        let interval = Duration::from_millis(100);
+        let mut timer = Timer {
+            interval,
+            message: (),
+            next: None,
+        };
+        assert_eq!(timer.tick(start), None);
+        assert_eq!(timer.tick(start + interval), Some(()));
+        // three intervals late: one tick, and the next one a full interval later
+        let late = start + interval * 5;
+        assert_eq!(timer.tick(late), Some(()));
+        assert_eq!(timer.tick(late), None);
+        assert_eq!(timer.tick(late + interval / 2), None);
+        assert_eq!(timer.tick(late + interval), Some(()));
+    }
+}
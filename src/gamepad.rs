@@ -0,0 +1,166 @@
+/// A gamepad button, named after the Xbox controller layout.
+#[derive(Clone, Copy, PartialEq, Eq, Hash, Debug)]
+pub enum GamepadButton {
+    A,
+    B,
+    X,
+    Y,
+    LeftBumper,
+    RightBumper,
+    Back,
+    Start,
+    Guide,
+    LeftThumb,
+    RightThumb,
+    DpadUp,
+    DpadRight,
+    DpadDown,
+    DpadLeft,
+}
+
+/// A gamepad axis. Sticks range from -1 to 1 with y pointing down, triggers from -1
+/// (released) to 1 (fully pressed).
+#[derive(Clone, Copy, PartialEq, Eq, Hash, Debug)]
+pub enum GamepadAxis {
+    LeftX,
+    LeftY,
+    RightX,
+    RightY,
+    LeftTrigger,
+    RightTrigger,
+}
+
+/// A change in the state of a gamepad. `id` tells apart gamepads connected at the same time.
+#[derive(Clone, PartialEq, Debug)]
+pub enum GamepadEvent {
+    Connected {
+        id: usize,
+        name: String,
+    },
+    Disconnected {
+        id: usize,
+    },
+    ButtonPressed {
+        id: usize,
+        button: GamepadButton,
+    },
+    ButtonReleased {
+        id: usize,
+        button: GamepadButton,
+    },
+    AxisMoved {
+        id: usize,
+        axis: GamepadAxis,
+        value: f32,
+    },
+}
+
+const BUTTONS: [(glfw::GamepadButton, GamepadButton); 15] = [
+    (glfw::GamepadButton::ButtonA, GamepadButton::A),
+    (glfw::GamepadButton::ButtonB, GamepadButton::B),
+    (glfw::GamepadButton::ButtonX, GamepadButton::X),
+    (glfw::GamepadButton::ButtonY, GamepadButton::Y),
+    (
+        glfw::GamepadButton::ButtonLeftBumper,
+        GamepadButton::LeftBumper,
+    ),
+    (
+        glfw::GamepadButton::ButtonRightBumper,
+        GamepadButton::RightBumper,
+    ),
+    (glfw::GamepadButton::ButtonBack, GamepadButton::Back),
+    (glfw::GamepadButton::ButtonStart, GamepadButton::Start),
+    (glfw::GamepadButton::ButtonGuide, GamepadButton::Guide),
+    (
+        glfw::GamepadButton::ButtonLeftThumb,
+        GamepadButton::LeftThumb,
+    ),
+    (
+        glfw::GamepadButton::ButtonRightThumb,
+        GamepadButton::RightThumb,
+    ),
+    (glfw::GamepadButton::ButtonDpadUp, GamepadButton::DpadUp),
+    (
+        glfw::GamepadButton::ButtonDpadRight,
+        GamepadButton::DpadRight,
+    ),
+    (glfw::GamepadButton::ButtonDpadDown, GamepadButton::DpadDown),
+    (glfw::GamepadButton::ButtonDpadLeft, GamepadButton::DpadLeft),
+];
+
+const AXES: [(glfw::GamepadAxis, GamepadAxis); 6] = [
+    (glfw::GamepadAxis::AxisLeftX, GamepadAxis::LeftX),
+    (glfw::GamepadAxis::AxisLeftY, GamepadAxis::LeftY),
+    (glfw::GamepadAxis::AxisRightX, GamepadAxis::RightX),
+    (glfw::GamepadAxis::AxisRightY, GamepadAxis::RightY),
+    (glfw::GamepadAxis::AxisLeftTrigger, GamepadAxis::LeftTrigger),
+    (
+        glfw::GamepadAxis::AxisRightTrigger,
+        GamepadAxis::RightTrigger,
+    ),
+];
+
+/// Axis changes smaller than this are treated as noise.
+const AXIS_EPSILON: f32 = 0.01;
+
+const MAX_GAMEPADS: usize = 16;
+
+#[derive(Clone, Copy)]
+struct State {
+    buttons: [bool; BUTTONS.len()],
+    axes: [f32; AXES.len()],
+}
+
+/// Compares the state of the gamepads with the previous poll. Joysticks without a
+/// gamepad mapping are ignored.
+#[derive(Default)]
+pub(crate) struct Gamepads {
+    states: [Option<State>; MAX_GAMEPADS],
+}
+
+impl Gamepads {
+    pub(crate) fn poll(&mut self, glfw: &glfw::Glfw) -> Vec<GamepadEvent> {
+        let mut events = Vec::new();
+        for (id, previous) in self.states.iter_mut().enumerate() {
+            let joystick = glfw::JoystickId::from_i32(id as i32).map(|id| glfw.get_joystick(id));
+            let gamepad = joystick.filter(|joystick| joystick.is_gamepad());
+            let state = match gamepad.as_ref().and_then(|g| g.get_gamepad_state()) {
+                Some(state) => state,
+                None => {
+                    if previous.take().is_some() {
+                        events.push(GamepadEvent::Disconnected { id });
+                    }
+                    continue;
+                }
+            };
+            let previous = previous.get_or_insert_with(|| {
+                let name = gamepad
+                    .and_then(|g| g.get_gamepad_name())
+                    .unwrap_or_default();
+                events.push(GamepadEvent::Connected { id, name });
+                State {
+                    buttons: [false; BUTTONS.len()],
+                    axes: [0.; AXES.len()],
+                }
+            });
+            for (i, &(glfw_button, button)) in BUTTONS.iter().enumerate() {
+                let pressed = state.get_button_state(glfw_button) != glfw::Action::Release;
+                if pressed != previous.buttons[i] {
+                    previous.buttons[i] = pressed;
+                    events.push(match pressed {
+                        true => GamepadEvent::ButtonPressed { id, button },
+                        false => GamepadEvent::ButtonReleased { id, button },
+                    });
+                }
+            }
+            for (i, &(glfw_axis, axis)) in AXES.iter().enumerate() {
+                let value = state.get_axis(glfw_axis);
+                if (value - previous.axes[i]).abs() >= AXIS_EPSILON {
+                    previous.axes[i] = value;
+                    events.push(GamepadEvent::AxisMoved { id, axis, value });
+                }
+            }
+        }
+        events
+    }
+}
@@ -0,0 +1,237 @@
+use std::time::{Duration, Instant};
+
+use crate::{Event, Modifiers, MouseButton, Point};
+
+/// Distance in framebuffer pixels the pointer may travel before a press turns into a drag.
+const DRAG_THRESHOLD: f64 = 5.;
+const MULTI_CLICK_INTERVAL: Duration = Duration::from_millis(500);
+const LONG_PRESS_DURATION: Duration = Duration::from_millis(600);
+
+struct Press {
+    button: MouseButton,
+    modifiers: Modifiers,
+    origin: Point<f64>,
+    time: Instant,
+    dragging: bool,
+    long: bool,
+}
+
+struct Click {
+    button: MouseButton,
+    position: Point<f64>,
+    time: Instant,
+    count: u32,
+}
+
+/// Recognizes clicks, long presses and drags in the pointer events of the window.
+/// Only the first of several held buttons makes gestures.
+#[derive(Default)]
+pub(crate) struct Gestures {
+    press: Option<Press>,
+    click: Option<Click>,
+}
+
+impl Gestures {
+    /// Returns the event along with the gestures it completes, in delivery order.
+    pub(crate) fn recognize(&mut self, event: Event, now: Instant) -> Vec<Event> {
+        match (&event, &mut self.press) {
+            (
+                &Event::PointerPressed {
+                    position,
+                    button,
+                    modifiers,
+                },
+                None,
+            ) => {
+                self.press = Some(Press {
+                    button,
+                    modifiers,
+                    origin: position,
+                    time: now,
+                    dragging: false,
+                    long: false,
+                });
+                vec![event]
+            }
+            (&Event::PointerMoved(position), Some(press)) => {
+                let offset = position - press.origin;
+                let mut events = vec![event];
+                if !press.dragging && offset.len() > DRAG_THRESHOLD {
+                    press.dragging = true;
+                    events.push(Event::DragStarted {
+                        position: press.origin,
+                        button: press.button,
+                    });
+                }
+                if press.dragging {
+                    events.push(Event::Dragged {
+                        position,
+                        offset,
+                        button: press.button,
+                    });
+                }
+                events
+            }
+            (
+                &Event::PointerReleased {
+                    position, button, ..
+                },
+                Some(press),
+            ) if press.button == button => {
+                let press = self.press.take().unwrap();
+                // gestures completed by the release are sent while the pressed drawer
+                // still holds the pointer capture
+                if press.dragging {
+                    let ended = Event::DragEnded {
+                        position,
+                        offset: position - press.origin,
+                        button,
+                    };
+                    return vec![ended, event];
+                }
+                if press.long {
+                    return vec![event];
+                }
+                let count = match &self.click {
+                    Some(click)
+                        if click.button == button
+                            && press.time - click.time <= MULTI_CLICK_INTERVAL
+                            && (press.origin - click.position).len() <= DRAG_THRESHOLD =>
+                    {
+                        click.count + 1
+                    }
+                    _ => 1,
+                };
+                self.click = Some(Click {
+                    button,
+                    position: press.origin,
+                    time: now,
+                    count,
+                });
+                let clicked = Event::Clicked {
+                    position,
+                    button,
+                    count,
+                    modifiers: press.modifiers,
+                };
+                vec![clicked, event]
+            }
+            _ => vec![event],
+        }
+    }
+
+    /// Reports a long press once the pointer has been held still long enough.
+    pub(crate) fn tick(&mut self, now: Instant) -> Option<Event> {
+        let press = self.press.as_mut()?;
+        if press.dragging || press.long || now - press.time < LONG_PRESS_DURATION {
+            return None;
+        }
+        press.long = true;
+        Some(Event::LongPressed {
+            position: press.origin,
+            button: press.button,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn press(x: f64) -> Event {
+        Event::PointerPressed {
+            position: Point::new(x, 0.),
+            button: MouseButton::Left,
+            modifiers: Modifiers::default(),
+        }
+    }
+
+    fn release(x: f64) -> Event {
+        Event::PointerReleased {
+            position: Point::new(x, 0.),
+            button: MouseButton::Left,
+            modifiers: Modifiers::default(),
+        }
+    }
+
+    fn click_count(events: &[Event]) -> Option<u32> {
+        events.iter().find_map(|event| match event {
+            Event::Clicked { count, .. } => Some(*count),
+            _ => None,
+        })
+    }
+
+    #[test]
+    fn counts_clicks_in_quick_succession() {
+        let mut gestures = Gestures::default();
+        let start = Instant::now();
+        let mut counts = vec![];
+        for i in 0..3 {
+            let now = start + Duration::from_millis(100 * i);
+            gestures.recognize(press(0.), now);
+            counts.push(click_count(&gestures.recognize(release(0.), now)));
+        }
+        assert_eq!(counts, [Some(1), Some(2), Some(3)]);
+
+        let later = start + MULTI_CLICK_INTERVAL * 2;
+        gestures.recognize(press(0.), later);
+        assert_eq!(
+            click_count(&gestures.recognize(release(0.), later)),
+            Some(1)
+        );
+    }
+
+    #[test]
+    fn sends_the_click_before_the_release() {
+        let mut gestures = Gestures::default();
+        let now = Instant::now();
+        gestures.recognize(press(0.), now);
+        let events = gestures.recognize(release(0.), now);
+        assert!(matches!(
+            events[..],
+            [Event::Clicked { .. }, Event::PointerReleased { .. }]
+        ));
+    }
+
+    #[test]
+    fn long_press_suppresses_the_click() {
+        let mut gestures = Gestures::default();
+        let now = Instant::now();
+        gestures.recognize(press(0.), now);
+        assert_eq!(gestures.tick(now + LONG_PRESS_DURATION / 2), None);
+        assert!(matches!(
+            gestures.tick(now + LONG_PRESS_DURATION),
+            Some(Event::LongPressed { .. })
+        ));
+        assert_eq!(gestures.tick(now + LONG_PRESS_DURATION * 2), None);
+        let events = gestures.recognize(release(0.), now + LONG_PRESS_DURATION * 2);
+        assert_eq!(click_count(&events), None);
+    }
+
+    #[test]
+    fn drags_past_the_threshold() {
+        let mut gestures = Gestures::default();
+        let now = Instant::now();
+        gestures.recognize(press(0.), now);
+        let moved = gestures.recognize(Event::PointerMoved(Point::new(DRAG_THRESHOLD, 0.)), now);
+        assert_eq!(moved.len(), 1);
+
+        let moved = gestures.recognize(Event::PointerMoved(Point::new(10., 0.)), now);
+        assert!(matches!(
+            moved[..],
+            [
+                Event::PointerMoved(_),
+                Event::DragStarted { .. },
+                Event::Dragged { .. }
+            ]
+        ));
+        assert_eq!(gestures.tick(now + LONG_PRESS_DURATION), None);
+
+        let released = gestures.recognize(release(10.), now);
+        assert!(matches!(
+            released[..],
+            [Event::DragEnded { offset, .. }, Event::PointerReleased { .. }]
+                if offset == Point::new(10., 0.)
+        ));
+    }
+}
@@ -0,0 +1,548 @@
+use std::str::FromStr;
+
+use crate::{Event, Modifiers};
+
+/// A physical key, named after its position on a US keyboard layout.
+#[derive(Clone, Copy, PartialEq, Eq, Hash, Debug)]
+pub enum KeyCode {
+    Space,
+    Apostrophe,
+    Comma,
+    Minus,
+    Period,
+    Slash,
+    Digit0,
+    Digit1,
+    Digit2,
+    Digit3,
+    Digit4,
+    Digit5,
+    Digit6,
+    Digit7,
+    Digit8,
+    Digit9,
+    Semicolon,
+    Equal,
+    A,
+    B,
+    C,
+    D,
+    E,
+    F,
+    G,
+    H,
+    I,
+    J,
+    K,
+    L,
+    M,
+    N,
+    O,
+    P,
+    Q,
+    R,
+    S,
+    T,
+    U,
+    V,
+    W,
+    X,
+    Y,
+    Z,
+    LeftBracket,
+    Backslash,
+    RightBracket,
+    GraveAccent,
+    Escape,
+    Enter,
+    Tab,
+    Backspace,
+    Insert,
+    Delete,
+    Right,
+    Left,
+    Down,
+    Up,
+    PageUp,
+    PageDown,
+    Home,
+    End,
+    CapsLock,
+    ScrollLock,
+    NumLock,
+    PrintScreen,
+    Pause,
+    F1,
+    F2,
+    F3,
+    F4,
+    F5,
+    F6,
+    F7,
+    F8,
+    F9,
+    F10,
+    F11,
+    F12,
+    F13,
+    F14,
+    F15,
+    F16,
+    F17,
+    F18,
+    F19,
+    F20,
+    F21,
+    F22,
+    F23,
+    F24,
+    F25,
+    Numpad0,
+    Numpad1,
+    Numpad2,
+    Numpad3,
+    Numpad4,
+    Numpad5,
+    Numpad6,
+    Numpad7,
+    Numpad8,
+    Numpad9,
+    NumpadDecimal,
+    NumpadDivide,
+    NumpadMultiply,
+    NumpadSubtract,
+    NumpadAdd,
+    NumpadEnter,
+    NumpadEqual,
+    LeftShift,
+    LeftControl,
+    LeftAlt,
+    LeftSuper,
+    RightShift,
+    RightControl,
+    RightAlt,
+    RightSuper,
+    Menu,
+    Unknown,
+}
+
+impl From<glfw::Key> for KeyCode {
+    fn from(key: glfw::Key) -> Self {
+        use glfw::Key;
+        match key {
+            Key::Space => Self::Space,
+            Key::Apostrophe => Self::Apostrophe,
+            Key::Comma => Self::Comma,
+            Key::Minus => Self::Minus,
+            Key::Period => Self::Period,
+            Key::Slash => Self::Slash,
+            Key::Num0 => Self::Digit0,
+            Key::Num1 => Self::Digit1,
+            Key::Num2 => Self::Digit2,
+            Key::Num3 => Self::Digit3,
+            Key::Num4 => Self::Digit4,
+            Key::Num5 => Self::Digit5,
+            Key::Num6 => Self::Digit6,
+            Key::Num7 => Self::Digit7,
+            Key::Num8 => Self::Digit8,
+            Key::Num9 => Self::Digit9,
+            Key::Semicolon => Self::Semicolon,
+            Key::Equal => Self::Equal,
+            Key::A => Self::A,
+            Key::B => Self::B,
+            Key::C => Self::C,
+            Key::D => Self::D,
+            Key::E => Self::E,
+            Key::F => Self::F,
+            Key::G => Self::G,
+            Key::H => Self::H,
+            Key::I => Self::I,
+            Key::J => Self::J,
+            Key::K => Self::K,
+            Key::L => Self::L,
+            Key::M => Self::M,
+            Key::N => Self::N,
+            Key::O => Self::O,
+            Key::P => Self::P,
+            Key::Q => Self::Q,
+            Key::R => Self::R,
+            Key::S => Self::S,
+            Key::T => Self::T,
+            Key::U => Self::U,
+            Key::V => Self::V,
+            Key::W => Self::W,
+            Key::X => Self::X,
+            Key::Y => Self::Y,
+            Key::Z => Self::Z,
+            Key::LeftBracket => Self::LeftBracket,
+            Key::Backslash => Self::Backslash,
+            Key::RightBracket => Self::RightBracket,
+            Key::GraveAccent => Self::GraveAccent,
+            Key::Escape => Self::Escape,
+            Key::Enter => Self::Enter,
+            Key::Tab => Self::Tab,
+            Key::Backspace => Self::Backspace,
+            Key::Insert => Self::Insert,
+            Key::Delete => Self::Delete,
+            Key::Right => Self::Right,
+            Key::Left => Self::Left,
+            Key::Down => Self::Down,
+            Key::Up => Self::Up,
+            Key::PageUp => Self::PageUp,
+            Key::PageDown => Self::PageDown,
+            Key::Home => Self::Home,
+            Key::End => Self::End,
+            Key::CapsLock => Self::CapsLock,
+            Key::ScrollLock => Self::ScrollLock,
+            Key::NumLock => Self::NumLock,
+            Key::PrintScreen => Self::PrintScreen,
+            Key::Pause => Self::Pause,
+            Key::F1 => Self::F1,
+            Key::F2 => Self::F2,
+            Key::F3 => Self::F3,
+            Key::F4 => Self::F4,
+            Key::F5 => Self::F5,
+            Key::F6 => Self::F6,
+            Key::F7 => Self::F7,
+            Key::F8 => Self::F8,
+            Key::F9 => Self::F9,
+            Key::F10 => Self::F10,
+            Key::F11 => Self::F11,
+            Key::F12 => Self::F12,
+            Key::F13 => Self::F13,
+            Key::F14 => Self::F14,
+            Key::F15 => Self::F15,
+            Key::F16 => Self::F16,
+            Key::F17 => Self::F17,
+            Key::F18 => Self::F18,
+            Key::F19 => Self::F19,
+            Key::F20 => Self::F20,
+            Key::F21 => Self::F21,
+            Key::F22 => Self::F22,
+            Key::F23 => Self::F23,
+            Key::F24 => Self::F24,
+            Key::F25 => Self::F25,
+            Key::Kp0 => Self::Numpad0,
+            Key::Kp1 => Self::Numpad1,
+            Key::Kp2 => Self::Numpad2,
+            Key::Kp3 => Self::Numpad3,
+            Key::Kp4 => Self::Numpad4,
+            Key::Kp5 => Self::Numpad5,
+            Key::Kp6 => Self::Numpad6,
+            Key::Kp7 => Self::Numpad7,
+            Key::Kp8 => Self::Numpad8,
+            Key::Kp9 => Self::Numpad9,
+            Key::KpDecimal => Self::NumpadDecimal,
+            Key::KpDivide => Self::NumpadDivide,
+            Key::KpMultiply => Self::NumpadMultiply,
+            Key::KpSubtract => Self::NumpadSubtract,
+            Key::KpAdd => Self::NumpadAdd,
+            Key::KpEnter => Self::NumpadEnter,
+            Key::KpEqual => Self::NumpadEqual,
+            Key::LeftShift => Self::LeftShift,
+            Key::LeftControl => Self::LeftControl,
+            Key::LeftAlt => Self::LeftAlt,
+            Key::LeftSuper => Self::LeftSuper,
+            Key::RightShift => Self::RightShift,
+            Key::RightControl => Self::RightControl,
+            Key::RightAlt => Self::RightAlt,
+            Key::RightSuper => Self::RightSuper,
+            Key::Menu => Self::Menu,
+            Key::World1 | Key::World2 | Key::Unknown => Self::Unknown,
+        }
+    }
+}
+
+/// A key pressed together with exactly the given modifiers.
+///
+/// Parsed from strings like `"Ctrl+Shift+Z"` or `"Alt+F4"`. Modifier names are `Shift`,
+/// `Ctrl`, `Alt` (or `Option`) and `Super` (or `Cmd`, `Win`), case-insensitively;
+/// `Mod` stands for Cmd on macOS and Ctrl elsewhere. The plus key, written `+` or `Plus`
+/// alone or after the modifiers, implies Shift as on a US layout.
+#[derive(Clone, Copy, PartialEq, Eq, Hash, Debug)]
+pub struct Shortcut {
+    pub key: KeyCode,
+    pub modifiers: Modifiers,
+}
+
+impl Shortcut {
+    pub fn new(key: KeyCode, modifiers: Modifiers) -> Self {
+        Self { key, modifiers }
+    }
+
+    /// Whether the event presses the shortcut. Repeated presses match as well.
+    pub fn matches(&self, event: &Event) -> bool {
+        matches!(event, Event::KeyPressed { key, modifiers, .. }
+            if *key == self.key && *modifiers == self.modifiers)
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum ShortcutError {
+    UnknownKey(String),
+    UnknownModifier(String),
+    MissingKey,
+    /// The shortcut is already bound to another message.
+    Conflict(Shortcut),
+}
+
+impl FromStr for Shortcut {
+    type Err = ShortcutError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let s = s.trim();
+        // a `+` on its own or after the last separator is the plus key
+        let (names, key) = if s == "+" {
+            ("", "plus")
+        } else if let Some(names) = s.strip_suffix("++") {
+            (names, "plus")
+        } else {
+            s.rsplit_once('+').unwrap_or(("", s))
+        };
+        let mut modifiers = Modifiers::default();
+        let key = match key.trim() {
+            // `+` is typed as Shift and `=` on a US layout
+            key if key.eq_ignore_ascii_case("plus") => {
+                modifiers.shift = true;
+                KeyCode::Equal
+            }
+            "" => return Err(ShortcutError::MissingKey),
+            key => parse_key(key)?,
+        };
+        let parts = names
+            .split('+')
+            .map(str::trim)
+            .filter(|_| !names.is_empty());
+        for part in parts {
+            let modifier = match part.to_lowercase().as_str() {
+                "shift" => &mut modifiers.shift,
+                "ctrl" | "control" => &mut modifiers.control,
+                "alt" | "option" => &mut modifiers.alt,
+                "super" | "cmd" | "command" | "win" | "logo" => &mut modifiers.logo,
+                "mod" if cfg!(target_os = "macos") => &mut modifiers.logo,
+                "mod" => &mut modifiers.control,
+                _ => return Err(ShortcutError::UnknownModifier(part.to_string())),
+            };
+            *modifier = true;
+        }
+        Ok(Self::new(key, modifiers))
+    }
+}
+
+fn parse_key(name: &str) -> Result<KeyCode, ShortcutError> {
+    const LETTERS: [KeyCode; 26] = [
+        KeyCode::A,
+        KeyCode::B,
+        KeyCode::C,
+        KeyCode::D,
+        KeyCode::E,
+        KeyCode::F,
+        KeyCode::G,
+        KeyCode::H,
+        KeyCode::I,
+        KeyCode::J,
+        KeyCode::K,
+        KeyCode::L,
+        KeyCode::M,
+        KeyCode::N,
+        KeyCode::O,
+        KeyCode::P,
+        KeyCode::Q,
+        KeyCode::R,
+        KeyCode::S,
+        KeyCode::T,
+        KeyCode::U,
+        KeyCode::V,
+        KeyCode::W,
+        KeyCode::X,
+        KeyCode::Y,
+        KeyCode::Z,
+    ];
+    const DIGITS: [KeyCode; 10] = [
+        KeyCode::Digit0,
+        KeyCode::Digit1,
+        KeyCode::Digit2,
+        KeyCode::Digit3,
+        KeyCode::Digit4,
+        KeyCode::Digit5,
+        KeyCode::Digit6,
+        KeyCode::Digit7,
+        KeyCode::Digit8,
+        KeyCode::Digit9,
+    ];
+    const FUNCTION_KEYS: [KeyCode; 25] = [
+        KeyCode::F1,
+        KeyCode::F2,
+        KeyCode::F3,
+        KeyCode::F4,
+        KeyCode::F5,
+        KeyCode::F6,
+        KeyCode::F7,
+        KeyCode::F8,
+        KeyCode::F9,
+        KeyCode::F10,
+        KeyCode::F11,
+        KeyCode::F12,
+        KeyCode::F13,
+        KeyCode::F14,
+        KeyCode::F15,
+        KeyCode::F16,
+        KeyCode::F17,
+        KeyCode::F18,
+        KeyCode::F19,
+        KeyCode::F20,
+        KeyCode::F21,
+        KeyCode::F22,
+        KeyCode::F23,
+        KeyCode::F24,
+        KeyCode::F25,
+    ];
+    let lower = name.to_lowercase();
+    let mut chars = lower.chars();
+    if let (Some(c), None) = (chars.next(), chars.next()) {
+        match c {
+            'a'..='z' => return Ok(LETTERS[c as usize - 'a' as usize]),
+            '0'..='9' => return Ok(DIGITS[c as usize - '0' as usize]),
+            _ => {}
+        }
+    }
+    if let Some(n) = lower
+        .strip_prefix('f')
+        .and_then(|n| n.parse::<usize>().ok())
+    {
+        if (1..=FUNCTION_KEYS.len()).contains(&n) {
+            return Ok(FUNCTION_KEYS[n - 1]);
+        }
+    }
+    Ok(match lower.as_str() {
+        "space" => KeyCode::Space,
+        "'" => KeyCode::Apostrophe,
+        "," => KeyCode::Comma,
+        "-" | "minus" => KeyCode::Minus,
+        "." => KeyCode::Period,
+        "/" => KeyCode::Slash,
+        ";" => KeyCode::Semicolon,
+        "=" | "equal" => KeyCode::Equal,
+        "[" => KeyCode::LeftBracket,
+        "\\" => KeyCode::Backslash,
+        "]" => KeyCode::RightBracket,
+        "`" => KeyCode::GraveAccent,
+        "esc" | "escape" => KeyCode::Escape,
+        "enter" | "return" => KeyCode::Enter,
+        "tab" => KeyCode::Tab,
+        "backspace" => KeyCode::Backspace,
+        "insert" => KeyCode::Insert,
+        "delete" | "del" => KeyCode::Delete,
+        "right" => KeyCode::Right,
+        "left" => KeyCode::Left,
+        "down" => KeyCode::Down,
+        "up" => KeyCode::Up,
+        "pageup" => KeyCode::PageUp,
+        "pagedown" => KeyCode::PageDown,
+        "home" => KeyCode::Home,
+        "end" => KeyCode::End,
+        "menu" => KeyCode::Menu,
+        _ => return Err(ShortcutError::UnknownKey(name.to_string())),
+    })
+}
+
+/// Keyboard shortcuts bound to messages. See `Subscriptions::shortcuts`.
+pub struct Shortcuts<M> {
+    pub(crate) bindings: Vec<(Shortcut, M)>,
+}
+
+impl<M> Shortcuts<M> {
+    pub fn new() -> Self {
+        Self { bindings: vec![] }
+    }
+
+    /// Binds a shortcut like `"Mod+S"` to a message. Fails if the shortcut does not parse
+    /// or is already bound.
+    pub fn bind(&mut self, shortcut: &str, message: M) -> Result<(), ShortcutError> {
+        self.bind_shortcut(shortcut.parse()?, message)
+    }
+
+    pub fn bind_shortcut(&mut self, shortcut: Shortcut, message: M) -> Result<(), ShortcutError> {
+        if self.bindings.iter().any(|(s, _)| *s == shortcut) {
+            return Err(ShortcutError::Conflict(shortcut));
+        }
+        self.bindings.push((shortcut, message));
+        Ok(())
+    }
+}
+
+impl<M> Default for Shortcuts<M> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn modifiers(shift: bool, control: bool, alt: bool, logo: bool) -> Modifiers {
+        Modifiers {
+            shift,
+            control,
+            alt,
+            logo,
+        }
+    }
+
+    #[test]
+    fn parses_modifiers_and_keys() {
+        let shortcut: Shortcut = "Ctrl+Shift+Z".parse().unwrap();
+        assert_eq!(
+            shortcut,
+            Shortcut::new(KeyCode::Z, modifiers(true, true, false, false))
+        );
+        let shortcut: Shortcut = "alt + f4".parse().unwrap();
+        assert_eq!(
+            shortcut,
+            Shortcut::new(KeyCode::F4, modifiers(false, false, true, false))
+        );
+    }
+
+    #[test]
+    fn plus_implies_shift() {
+        let expected = Shortcut::new(KeyCode::Equal, modifiers(true, true, false, false));
+        assert_eq!("Ctrl++".parse(), Ok(expected));
+        assert_eq!("Ctrl+Plus".parse(), Ok(expected));
+        let expected = Shortcut::new(KeyCode::Equal, modifiers(true, false, false, false));
+        assert_eq!("+".parse(), Ok(expected));
+        assert_eq!("++".parse(), Ok(expected));
+        assert_eq!("Plus".parse(), Ok(expected));
+        assert_eq!("Ctrl+".parse::<Shortcut>(), Err(ShortcutError::MissingKey));
+    }
+
+    #[test]
+    fn mod_is_the_platform_command_key() {
+        let macos = cfg!(target_os = "macos");
+        let expected = Shortcut::new(KeyCode::S, modifiers(false, !macos, false, macos));
+        assert_eq!("Mod+S".parse(), Ok(expected));
+    }
+
+    #[test]
+    fn rejects_unknown_names() {
+        assert_eq!(
+            "Ctrl+Foo".parse::<Shortcut>(),
+            Err(ShortcutError::UnknownKey("Foo".to_string()))
+        );
+        assert_eq!(
+            "Hyper+S".parse::<Shortcut>(),
+            Err(ShortcutError::UnknownModifier("Hyper".to_string()))
+        );
+        assert_eq!("Ctrl+".parse::<Shortcut>(), Err(ShortcutError::MissingKey));
+    }
+
+    #[test]
+    fn reports_conflicting_bindings() {
+        let mut shortcuts = Shortcuts::new();
+        assert_eq!(shortcuts.bind("Ctrl+S", 1), Ok(()));
+        assert_eq!(
+            shortcuts.bind("control+s", 2),
+            Err(ShortcutError::Conflict("Ctrl+S".parse().unwrap()))
+        );
+    }
+}
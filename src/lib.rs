@@ -2,19 +2,31 @@
 pub mod graphics;
 pub mod command;
 pub mod event;
+pub mod gamepad;
+pub mod keyboard;
 pub mod math;
 pub mod model;
 pub mod program;
 pub mod view;
 pub mod drawer;
+pub mod window;
+
+mod gesture;
+mod runtime;
+mod task;
+mod watch;
 
 pub use command::*;
 pub use event::*;
+pub use gamepad::*;
+pub use keyboard::*;
 pub use math::*;
 pub use model::*;
 pub use program::*;
 pub use view::*;
 pub use drawer::*;
+pub use window::*;
+pub use task::Sender;
 
 #[cfg(test)]
 mod tests {
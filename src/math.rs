@@ -1,6 +1,6 @@
 use std::ops::{Add, Div, Mul, Neg, Range, Sub};
 
-#[derive(Debug, Clone, Copy)]
+#[derive(Debug, Clone, Copy, PartialEq)]
 pub struct Point<T = i32> {
     pub x: T,
     pub y: T,
@@ -91,6 +91,12 @@ impl Point<f32> {
     }
 }
 
+impl Point<f64> {
+    pub fn len(self) -> f64 {
+        (self.x.powi(2) + self.y.powi(2)).sqrt()
+    }
+}
+
 impl Default for Point<i32> {
     fn default() -> Self {
         Self::zero()
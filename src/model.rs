@@ -2,9 +2,9 @@ use crate::{Command, Subscriptions, View};
 
 pub trait Model: Sized {
     type Flags;
-    type Message: Copy;
+    type Message: Clone + Send + 'static;
     fn init(flags: Self::Flags) -> (Self, Command<Self::Message>);
-    fn subscriptions() -> Subscriptions<Self::Message>;
+    fn subscriptions(&self) -> Subscriptions<Self::Message>;
     fn update(&mut self, message: Self::Message) -> Command<Self::Message>;
     fn view(&self) -> Box<dyn View<Self::Message>>;
 }
@@ -1,16 +1,24 @@
-use std::rc::Rc;
+use std::{any::Any, sync::mpsc};
 
-use crate::{graphics::Context, Bounds, Command, Model, Point, Subscriptions, View};
-use glfw::{Context as _, InitError, OpenGlProfileHint, WindowEvent, WindowHint, WindowMode};
+use crate::{
+    graphics::Context, runtime::Runtime, window::pixel_image, Command, Model, Point, Sender,
+    Subscriptions, View, WindowSettings,
+};
+use glfw::{InitError, OpenGlProfileHint, WindowHint, WindowMode};
+
+type Inbox<M> = (Sender<M>, mpsc::Receiver<M>);
 
 pub struct Program {
     glfw: glfw::Glfw,
+    inbox: Option<Box<dyn Any>>,
 }
 
 #[derive(Debug)]
 pub enum Error {
     InitError(InitError),
     WindowCreationError,
+    /// No monitor with the index passed to `WindowSettings::fullscreen` is connected.
+    MonitorNotFound(usize),
     CanvasError(String),
 }
 
@@ -26,82 +34,115 @@ impl From<String> for Error {
     }
 }
 
-pub enum Size {
-    Pixels(u32, u32),
-    Max,
-}
-
-impl Default for Size {
-    fn default() -> Self {
-        Self::Pixels(600, 400)
-    }
-}
-
 impl Program {
     pub fn new() -> Result<Self, Error> {
         let mut glfw = glfw::init_no_callbacks()?;
         glfw.window_hint(WindowHint::ContextVersion(3, 3));
         glfw.window_hint(WindowHint::OpenGlProfile(OpenGlProfileHint::Core));
-        glfw.window_hint(WindowHint::Samples(Some(4))); // enables antialiasing
-        Ok(Self { glfw })
+        Ok(Self { glfw, inbox: None })
+    }
+
+    /// Returns a handle that injects messages into the model of the next `run`
+    /// from other threads. Handles of a different message type are disconnected.
+    pub fn sender<M: Send + 'static>(&mut self) -> Sender<M> {
+        if let Some((sender, _)) = self
+            .inbox
+            .as_ref()
+            .and_then(|i| i.downcast_ref::<Inbox<M>>())
+        {
+            return sender.clone();
+        }
+        let inbox = self.new_inbox::<M>();
+        let sender = inbox.0.clone();
+        self.inbox = Some(Box::new(inbox));
+        sender
+    }
+
+    fn new_inbox<M>(&mut self) -> Inbox<M> {
+        let (sender, receiver) = mpsc::channel();
+        (Sender::new(sender, &mut self.glfw), receiver)
     }
 
-    pub fn run<T: Model>(&mut self, size: Size, title: &str, flags: T::Flags) -> Result<(), Error> {
-        let (width, height) = match size {
-            Size::Pixels(width, height) => (width, height),
-            Size::Max => (600, 400),
-        };
-        let (mut window, events) = self
-            .glfw
-            .create_window(width, height, title, WindowMode::Windowed)
-            .ok_or(Error::WindowCreationError)?;
+    fn take_inbox<M: 'static>(&mut self) -> Inbox<M> {
+        match self.inbox.take().map(|inbox| inbox.downcast::<Inbox<M>>()) {
+            Some(Ok(inbox)) => *inbox,
+            _ => self.new_inbox(),
+        }
+    }
+
+    pub fn run<T: Model>(
+        &mut self,
+        settings: WindowSettings,
+        flags: T::Flags,
+    ) -> Result<(), Error> {
+        let hints = [
+            WindowHint::Samples(settings.samples), // enables antialiasing
+            WindowHint::Resizable(settings.resizable),
+            WindowHint::Decorated(settings.decorated),
+            WindowHint::Maximized(settings.maximized),
+            WindowHint::TransparentFramebuffer(settings.transparent),
+            WindowHint::Floating(settings.always_on_top),
+        ];
+        for hint in hints {
+            self.glfw.window_hint(hint);
+        }
+        let (width, height) = settings.size;
+        let title = &settings.title;
+        let (mut window, events) = match settings.fullscreen {
+            None => self
+                .glfw
+                .create_window(width, height, title, WindowMode::Windowed)
+                .ok_or(Error::WindowCreationError),
+            Some(index) => self.glfw.with_connected_monitors(|glfw, monitors| {
+                let monitor = monitors.get(index).ok_or(Error::MonitorNotFound(index))?;
+                let mode = monitor
+                    .get_video_mode()
+                    .ok_or(Error::MonitorNotFound(index))?;
+                glfw.create_window(
+                    mode.width,
+                    mode.height,
+                    title,
+                    WindowMode::FullScreen(monitor),
+                )
+                .ok_or(Error::WindowCreationError)
+            }),
+        }?;
+
+        if let (Some((x, y)), None) = (settings.position, settings.fullscreen) {
+            window.set_pos(x, y);
+        }
+        let (min, max) = (settings.min_size, settings.max_size);
+        window.set_size_limits(
+            min.map(|(width, _)| width),
+            min.map(|(_, height)| height),
+            max.map(|(width, _)| width),
+            max.map(|(_, height)| height),
+        );
+        if let Some(icon) = &settings.icon {
+            window.set_icon_from_pixels(vec![pixel_image(icon)]);
+        }
 
         window.set_mouse_button_polling(true);
         window.set_cursor_pos_polling(true);
+        window.set_cursor_enter_polling(true);
+        window.set_scroll_polling(true);
+        window.set_drag_and_drop_polling(true);
         window.set_key_polling(true);
         window.set_char_polling(true);
         window.set_framebuffer_size_polling(true);
+        window.set_pos_polling(true);
+        window.set_focus_polling(true);
+        window.set_iconify_polling(true);
+        window.set_content_scale_polling(true);
+        window.set_close_polling(true);
 
-        window.maximize();
-
-        let mut size: Point = window.get_framebuffer_size().into();
+        let size: Point = window.get_framebuffer_size().into();
         let dpi = window.get_size().0 as f32 / size.x as f32;
 
-        let mut context = Context::new(size, dpi, |str| window.get_proc_address(str))?;
-
-        let (mut model, cmd) = T::init(flags);
-        let view = model.view();
-        let mut drawer = view.new_drawer(&context);
-        drawer.set_bounds(&context, Bounds::from_size(size));
-
-        while !window.should_close() {
-            context.clear();
-            drawer.draw(&context);
-
-            window.swap_buffers();
-            self.glfw.poll_events();
-            for (_, event) in glfw::flush_messages(&events) {
-                match event {
-                    WindowEvent::FramebufferSize(width, height) => {
-                        size = Point::new(width, height);
-                        context.set_size(size);
-                        drawer.set_bounds(&context, Bounds::from_size(size))
-                    }
-                    _ => (),
-                }
-                match drawer.process(event) {
-                    Some(message) => {
-                        match model.update(message) {
-                            Command::Update => {
-                                drawer = model.view().new_drawer(&context);
-                            }
-                            _ => {}
-                        };
-                    }
-                    None => {}
-                }
-            }
-        }
+        let context = Context::new(size, dpi, |str| window.get_proc_address(str))?;
+
+        let inbox = self.take_inbox::<T::Message>();
+        Runtime::<T>::new(inbox, window, events, context, size, flags).run(&mut self.glfw);
         Ok(())
     }
 }
@@ -118,7 +159,7 @@ impl<V: View<()> + 'static> Model for EmptyModel<V> {
         (Self { view }, Command::None)
     }
 
-    fn subscriptions() -> Subscriptions<Self::Message> {
+    fn subscriptions(&self) -> Subscriptions<Self::Message> {
         Subscriptions::default()
     }
 
@@ -134,10 +175,9 @@ impl<V: View<()> + 'static> Model for EmptyModel<V> {
 impl Program {
     pub fn show<V: View<()> + 'static>(
         &mut self,
-        size: Size,
-        title: &str,
+        settings: WindowSettings,
         view: fn() -> V,
     ) -> Result<(), Error> {
-        self.run::<EmptyModel<V>>(size, title, view)
+        self.run::<EmptyModel<V>>(settings, view)
     }
 }
@@ -0,0 +1,348 @@
+use std::{collections::VecDeque, sync::mpsc, time::Instant};
+
+use crate::{
+    gamepad::Gamepads,
+    gesture::Gestures,
+    graphics::Context,
+    task::{Executor, Sender},
+    Bounds, Command, Drawer, Event, KeyCode, Model, Modifiers, Point, ScrollDelta, Status,
+    Subscriptions, WindowCommand,
+};
+use glfw::{Action, Context as _, GlfwReceiver, Key, PWindow, WindowEvent, WindowMode};
+
+/// Height of a scrolled line in screen coordinates.
+const LINE_HEIGHT: f64 = 20.;
+
+pub(crate) struct Runtime<T: Model> {
+    window: PWindow,
+    events: GlfwReceiver<(f64, WindowEvent)>,
+    context: Context,
+    size: Point,
+    cursor: Point<f64>,
+    gestures: Gestures,
+    gamepads: Gamepads,
+    /// Position and size of the window before it went fullscreen.
+    windowed: Option<((i32, i32), (i32, i32))>,
+    model: T,
+    subscriptions: Subscriptions<T::Message>,
+    drawer: Box<dyn Drawer<T::Message>>,
+    messages: VecDeque<T::Message>,
+    outdated: bool,
+    /// Drawer to focus once the view is up to date.
+    focus: Option<crate::Key>,
+    executor: Executor,
+    sender: Sender<T::Message>,
+    receiver: mpsc::Receiver<T::Message>,
+    last_frame: Instant,
+}
+
+impl<T: Model> Runtime<T> {
+    pub(crate) fn new(
+        (sender, receiver): (Sender<T::Message>, mpsc::Receiver<T::Message>),
+        window: PWindow,
+        events: GlfwReceiver<(f64, WindowEvent)>,
+        context: Context,
+        size: Point,
+        flags: T::Flags,
+    ) -> Self {
+        let (model, command) = T::init(flags);
+        let subscriptions = model.subscriptions();
+        let mut drawer = model.view().new_drawer(&context);
+        drawer.set_bounds(&context, Bounds::from_size(size));
+        let mut runtime = Self {
+            window,
+            events,
+            context,
+            size,
+            cursor: Point::new(0., 0.),
+            gestures: Gestures::default(),
+            gamepads: Gamepads::default(),
+            windowed: None,
+            model,
+            subscriptions,
+            drawer,
+            messages: VecDeque::new(),
+            outdated: false,
+            focus: None,
+            executor: Executor::default(),
+            sender,
+            receiver,
+            last_frame: Instant::now(),
+        };
+        runtime.execute(command);
+        runtime.dispatch();
+        runtime
+    }
+
+    pub(crate) fn run(mut self, glfw: &mut glfw::Glfw) {
+        while !self.window.should_close() {
+            self.context.clear();
+            self.drawer.draw(&self.context);
+
+            self.window.swap_buffers();
+            glfw.poll_events();
+            let events: Vec<WindowEvent> = glfw::flush_messages(&self.events)
+                .map(|(_, event)| event)
+                .collect();
+            if events.contains(&WindowEvent::Close) {
+                // glfw closes the window on its own; it is closed again when no
+                // subscription intercepts the request
+                self.window.set_should_close(false);
+            }
+            for event in events {
+                if self.window.should_close() {
+                    break;
+                }
+                self.process(event);
+                self.dispatch();
+            }
+            self.messages.extend(self.receiver.try_iter());
+            self.tick();
+            self.dispatch();
+        }
+    }
+
+    fn process(&mut self, event: WindowEvent) {
+        let event = match event {
+            WindowEvent::CursorPos(x, y) => {
+                self.cursor = self.to_framebuffer(x, y);
+                Event::PointerMoved(self.cursor)
+            }
+            WindowEvent::FileDrop(paths) => {
+                // the cursor does not report its position while dragging on every platform
+                let (x, y) = self.window.get_cursor_pos();
+                self.cursor = self.to_framebuffer(x, y);
+                Event::FilesDropped {
+                    position: self.cursor,
+                    paths,
+                }
+            }
+            WindowEvent::Scroll(x, y) => {
+                let scale = self.size.x as f64 / self.window.get_size().0 as f64;
+                let lines = Point::new(x, y);
+                Event::Scrolled {
+                    position: self.cursor,
+                    delta: ScrollDelta {
+                        lines,
+                        pixels: lines.mul(LINE_HEIGHT * scale),
+                    },
+                    modifiers: self.modifiers(),
+                }
+            }
+            event => match Event::from_glfw(event, self.cursor) {
+                Some(event) => event,
+                None => return,
+            },
+        };
+        for event in self.gestures.recognize(event, Instant::now()) {
+            self.deliver(event);
+        }
+    }
+
+    /// Converts window coordinates to framebuffer pixels with y pointing up.
+    fn to_framebuffer(&self, x: f64, y: f64) -> Point<f64> {
+        let (width, height) = self.window.get_size();
+        Point::new(
+            x * self.size.x as f64 / width as f64,
+            (height as f64 - y) * self.size.y as f64 / height as f64,
+        )
+    }
+
+    /// Passes an event to the subscriptions and the drawers.
+    fn deliver(&mut self, event: Event) {
+        if let Event::Resized(size) = event {
+            self.size = size;
+            self.context.set_size(self.size);
+            self.drawer
+                .set_bounds(&self.context, Bounds::from_size(self.size));
+        }
+        if event == Event::CloseRequested {
+            let intercepted = self.subscriptions.close_requested();
+            if intercepted.is_empty() {
+                self.window.set_should_close(true);
+            }
+            self.messages.extend(intercepted);
+        }
+        self.messages.extend(self.subscriptions.process(&event));
+        if let Some(message) = self.subscriptions.shortcut(&event) {
+            self.messages.push_back(message);
+            return;
+        }
+        let origin = self.drawer.bounds().min;
+        let event = event.translate(Point::new(-origin.x as f64, -origin.y as f64));
+        let mut messages = Vec::new();
+        let status = self.drawer.process(event.clone(), &mut messages);
+        if let (
+            Status::Ignored,
+            Event::KeyPressed {
+                key: KeyCode::Tab,
+                modifiers,
+                ..
+            },
+        ) = (status, event)
+        {
+            crate::drawer::cycle_focus(self.drawer.as_mut(), modifiers.shift, &mut messages);
+        }
+        self.messages.extend(messages);
+    }
+
+    /// Glfw reports no modifiers with scroll events, so they are read from the keyboard state.
+    fn modifiers(&self) -> Modifiers {
+        let held = |keys: [Key; 2]| {
+            keys.iter()
+                .any(|&key| self.window.get_key(key) != Action::Release)
+        };
+        Modifiers {
+            shift: held([Key::LeftShift, Key::RightShift]),
+            control: held([Key::LeftControl, Key::RightControl]),
+            alt: held([Key::LeftAlt, Key::RightAlt]),
+            logo: held([Key::LeftSuper, Key::RightSuper]),
+        }
+    }
+
+    fn tick(&mut self) {
+        let now = Instant::now();
+        let elapsed = now - self.last_frame;
+        self.last_frame = now;
+        if let Some(event) = self.gestures.tick(now) {
+            self.deliver(event);
+        }
+        self.messages.extend(self.subscriptions.tick(now, elapsed));
+        if self.subscriptions.wants_gamepads() {
+            for event in self.gamepads.poll(&self.window.glfw) {
+                self.messages
+                    .extend(self.subscriptions.process_gamepad(&event));
+            }
+        } else {
+            // a later subscription hears about the gamepads that are already connected
+            self.gamepads = Gamepads::default();
+        }
+    }
+
+    fn execute(&mut self, command: Command<T::Message>) {
+        match command {
+            Command::None => {}
+            Command::Update => self.outdated = true,
+            Command::Terminate => self.window.set_should_close(true),
+            Command::Message(message) => self.messages.push_back(message),
+            Command::Focus(id) => self.focus = Some(id),
+            Command::Window(command) => self.control(command),
+            Command::SetClipboard(text) => self.window.set_clipboard_string(&text),
+            Command::ReadClipboard(map) => {
+                let text = self.window.get_clipboard_string();
+                self.messages.push_back(map(text));
+            }
+            Command::Perform(task) => {
+                let sender = self.sender.clone();
+                self.executor.spawn(move || {
+                    let _ = sender.send(task());
+                });
+            }
+            Command::Batch(commands) => {
+                for command in commands {
+                    self.execute(command);
+                }
+            }
+        }
+    }
+
+    fn control(&mut self, command: WindowCommand) {
+        match command {
+            WindowCommand::SetTitle(title) => self.window.set_title(&title),
+            WindowCommand::Resize(width, height) => {
+                self.window.set_size(width as i32, height as i32)
+            }
+            WindowCommand::Move(x, y) => self.window.set_pos(x, y),
+            WindowCommand::Maximize => self.window.maximize(),
+            WindowCommand::Minimize => self.window.iconify(),
+            WindowCommand::Restore => self.window.restore(),
+            WindowCommand::ToggleFullscreen => self.toggle_fullscreen(),
+            WindowCommand::RequestAttention => self.window.request_attention(),
+        }
+    }
+
+    fn toggle_fullscreen(&mut self) {
+        let fullscreen = self
+            .window
+            .with_window_mode(|mode| matches!(mode, WindowMode::FullScreen(_)));
+        if fullscreen {
+            // a window created fullscreen has no previous geometry, so it is centered instead
+            let (width, height) = self.window.get_size();
+            let ((x, y), (width, height)) = self
+                .windowed
+                .take()
+                .unwrap_or(((width / 4, height / 4), (width / 2, height / 2)));
+            self.window.set_monitor(
+                WindowMode::Windowed,
+                x,
+                y,
+                width as u32,
+                height as u32,
+                None,
+            );
+        } else {
+            self.windowed = Some((self.window.get_pos(), self.window.get_size()));
+            let mut glfw = self.window.glfw.clone();
+            let window = &mut self.window;
+            glfw.with_primary_monitor(|_, monitor| {
+                let monitor = match monitor {
+                    Some(monitor) => monitor,
+                    None => return,
+                };
+                if let Some(mode) = monitor.get_video_mode() {
+                    window.set_monitor(
+                        WindowMode::FullScreen(monitor),
+                        0,
+                        0,
+                        mode.width,
+                        mode.height,
+                        Some(mode.refresh_rate),
+                    );
+                }
+            });
+        }
+    }
+
+    /// Feeds queued messages to the model until the queue is empty or the program is terminated.
+    fn dispatch(&mut self) {
+        loop {
+            let updated = !self.messages.is_empty();
+            while let Some(message) = self.messages.pop_front() {
+                if self.window.should_close() {
+                    self.messages.clear();
+                    return;
+                }
+                let command = self.model.update(message);
+                // messages of the command go ahead of the ones queued before it
+                let queued = std::mem::take(&mut self.messages);
+                self.execute(command);
+                self.messages.extend(queued);
+            }
+            if updated {
+                let mut subscriptions = self.model.subscriptions();
+                subscriptions.inherit(std::mem::take(&mut self.subscriptions));
+                self.subscriptions = subscriptions;
+            }
+            if self.outdated {
+                self.outdated = false;
+                let view = self.model.view();
+                let mut messages = Vec::new();
+                if view.reconcile(&self.context, &mut self.drawer, &mut messages) {
+                    self.drawer
+                        .set_bounds(&self.context, Bounds::from_size(self.size));
+                }
+                self.messages.extend(messages);
+            }
+            // focus moves after reconciliation, so that it can reach drawers created
+            // by the same update, and the focus messages are dispatched in turn
+            if let Some(id) = self.focus.take() {
+                let mut messages = Vec::new();
+                self.drawer.focus_id(id, &mut messages);
+                self.messages.extend(messages);
+            }
+            if self.messages.is_empty() {
+                return;
+            }
+        }
+    }
+}
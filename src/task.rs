@@ -0,0 +1,82 @@
+use std::{
+    panic::{self, AssertUnwindSafe},
+    sync::{mpsc, Arc, Mutex},
+    thread,
+};
+
+use glfw::ThreadSafeGlfw;
+
+type Job = Box<dyn FnOnce() + Send>;
+
+/// A fixed pool of worker threads running tasks off the UI thread.
+/// Workers are started on the first spawned task.
+#[derive(Default)]
+pub(crate) struct Executor {
+    jobs: Option<mpsc::Sender<Job>>,
+}
+
+impl Executor {
+    pub(crate) fn spawn(&mut self, job: impl FnOnce() + Send + 'static) {
+        let jobs = self.jobs.get_or_insert_with(Self::start);
+        // workers live as long as the sender, so sending cannot fail
+        let _ = jobs.send(Box::new(job));
+    }
+
+    fn start() -> mpsc::Sender<Job> {
+        let (sender, receiver) = mpsc::channel::<Job>();
+        let receiver = Arc::new(Mutex::new(receiver));
+        let workers = thread::available_parallelism().map_or(4, |n| n.get());
+        for _ in 0..workers {
+            let receiver = receiver.clone();
+            thread::spawn(move || loop {
+                let job = match receiver.lock() {
+                    Ok(receiver) => receiver.recv(),
+                    Err(_) => return,
+                };
+                match job {
+                    // a panicking task is reported by the panic hook and produces no
+                    // message, but the worker stays in the pool
+                    Ok(job) => {
+                        let _ = panic::catch_unwind(AssertUnwindSafe(job));
+                    }
+                    Err(_) => return,
+                }
+            });
+        }
+        sender
+    }
+}
+
+/// Delivers messages to the running program from any thread, waking its event loop.
+/// Obtained from `Program::sender`.
+pub struct Sender<M> {
+    sender: mpsc::Sender<M>,
+    waker: Arc<Mutex<ThreadSafeGlfw>>,
+}
+
+impl<M> Sender<M> {
+    pub(crate) fn new(sender: mpsc::Sender<M>, glfw: &mut glfw::Glfw) -> Self {
+        Self {
+            sender,
+            waker: Arc::new(Mutex::new(ThreadSafeGlfw::from(glfw))),
+        }
+    }
+
+    /// Queues the message for `Model::update`. Fails once the program has stopped.
+    pub fn send(&self, message: M) -> Result<(), mpsc::SendError<M>> {
+        self.sender.send(message)?;
+        if let Ok(glfw) = self.waker.lock() {
+            glfw.post_empty_event();
+        }
+        Ok(())
+    }
+}
+
+impl<M> Clone for Sender<M> {
+    fn clone(&self) -> Self {
+        Self {
+            sender: self.sender.clone(),
+            waker: self.waker.clone(),
+        }
+    }
+}
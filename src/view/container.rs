@@ -1,11 +1,12 @@
 use std::marker::PhantomData;
 
-use crate::{graphics::Context, CommonDrawer, Drawer, Orientation, Style, View};
+use crate::{graphics::Context, CommonDrawer, Drawer, Key, Orientation, Style, View};
 
 pub struct Container<M> {
     style: Style,
     orientation: Orientation,
     children: Vec<Box<dyn View<M>>>,
+    keys: Vec<Option<Key>>,
 }
 
 impl<M> Container<M> {
@@ -16,24 +17,77 @@ impl<M> Container<M> {
     ) -> Box<Self> {
         Box::new(Self {
             style,
+            keys: vec![None; children.len()],
             children,
             orientation,
         })
     }
+
+    /// Creates a container whose children keep their drawers when they are reordered,
+    /// inserted or removed, as long as their keys stay the same. `Command::Focus` with a
+    /// child's key focuses the first focusable drawer within it.
+    pub fn keyed(
+        orientation: Orientation,
+        style: Style,
+        children: Vec<(Key, Box<dyn View<M>>)>,
+    ) -> Box<Self> {
+        let (keys, children) = children
+            .into_iter()
+            .map(|(key, child)| (Some(key), child))
+            .unzip();
+        Box::new(Self {
+            style,
+            children,
+            keys,
+            orientation,
+        })
+    }
 }
 
 impl<M: 'static> View<M> for Container<M> {
     fn new_drawer(&self, context: &Context) -> Box<dyn Drawer<M>> {
-        Box::new(CommonDrawer {
-            bounds: Default::default(),
-            style: self.style,
-            orientation: self.orientation,
-            children: self
-                .children
+        Box::new(CommonDrawer::new(
+            self.orientation,
+            self.style,
+            self.children
                 .iter()
                 .map(|child| child.new_drawer(context))
                 .collect(),
-        })
+            self.keys.clone(),
+        ))
+    }
+
+    fn reconcile(
+        &self,
+        context: &Context,
+        drawer: &mut Box<dyn Drawer<M>>,
+        messages: &mut Vec<M>,
+    ) -> bool {
+        let common = match drawer
+            .as_mut()
+            .as_any_mut()
+            .downcast_mut::<CommonDrawer<M>>()
+        {
+            Some(common) => common,
+            None => {
+                drawer.blur(messages);
+                *drawer = self.new_drawer(context);
+                return true;
+            }
+        };
+        let resized = common.style.width != self.style.width
+            || common.style.height != self.style.height
+            || common.style.margin != self.style.margin;
+        let mut relayout =
+            common.style.padding != self.style.padding || common.orientation != self.orientation;
+        common.style = self.style;
+        common.orientation = self.orientation;
+        relayout |= common.reconcile_children(context, &self.children, &self.keys, messages);
+
+        if relayout && !resized {
+            common.layout(context);
+        }
+        resized
     }
 }
 
@@ -45,6 +99,10 @@ impl<M> Row<M> {
     pub fn new(style: Style, children: Vec<Box<dyn View<M>>>) -> Box<Container<M>> {
         Container::new(Orientation::Horizontal, style, children)
     }
+
+    pub fn keyed(style: Style, children: Vec<(Key, Box<dyn View<M>>)>) -> Box<Container<M>> {
+        Container::keyed(Orientation::Horizontal, style, children)
+    }
 }
 
 pub struct Column<M> {
@@ -55,4 +113,8 @@ impl<M> Column<M> {
     pub fn new(style: Style, children: Vec<Box<dyn View<M>>>) -> Box<Container<M>> {
         Container::new(Orientation::Vertical, style, children)
     }
+
+    pub fn keyed(style: Style, children: Vec<(Key, Box<dyn View<M>>)>) -> Box<Container<M>> {
+        Container::keyed(Orientation::Vertical, style, children)
+    }
 }
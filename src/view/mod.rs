@@ -6,13 +6,28 @@ use std::rc::Rc;
 
 use crate::{graphics::Context, Drawer};
 
-pub use self::util::{Indents, Length, Orientation};
+pub use self::util::{Indents, Key, Length, Orientation};
 pub use style::Style;
 
 type MouseButton = glfw::MouseButton;
 
 pub trait View<M = ()> {
     fn new_drawer(&self, context: &Context) -> Box<dyn Drawer<M>>;
+
+    /// Applies the view to a drawer built from an earlier view, keeping the drawer's
+    /// state where possible. Returns `true` if the drawer has to be laid out again by its parent.
+    /// By default the drawer is rebuilt. A drawer that is discarded must be blurred first,
+    /// with the resulting messages pushed to `messages`.
+    fn reconcile(
+        &self,
+        context: &Context,
+        drawer: &mut Box<dyn Drawer<M>>,
+        messages: &mut Vec<M>,
+    ) -> bool {
+        drawer.blur(messages);
+        *drawer = self.new_drawer(context);
+        true
+    }
 }
 
 pub trait ViewFn<M>: Fn(Rc<Context>) -> Box<dyn Drawer<M>> {}
@@ -1,12 +1,30 @@
+use std::{
+    collections::hash_map::DefaultHasher,
+    hash::{Hash, Hasher},
+};
+
 use crate::math::{Bounds, Point};
 
-#[derive(Clone, Copy)]
+/// Identifies a child among its siblings in keyed containers, so that its drawer survives
+/// reordering, or a drawer within the window for `Command::Focus`.
+#[derive(Clone, Copy, PartialEq, Eq, Hash, Debug)]
+pub struct Key(u64);
+
+impl Key {
+    pub fn new(value: impl Hash) -> Self {
+        let mut hasher = DefaultHasher::new();
+        value.hash(&mut hasher);
+        Self(hasher.finish())
+    }
+}
+
+#[derive(Clone, Copy, PartialEq)]
 pub enum Orientation {
     Vertical,
     Horizontal,
 }
 
-#[derive(Copy, Clone)]
+#[derive(Copy, Clone, PartialEq)]
 pub enum Length {
     Pixels(u32),
     Fill,
@@ -39,7 +57,7 @@ impl Length {
     }
 }
 
-#[derive(Copy, Clone)]
+#[derive(Copy, Clone, PartialEq)]
 pub struct Indents {
     left: u32,
     top: u32,
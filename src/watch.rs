@@ -0,0 +1,76 @@
+use std::{
+    collections::{BTreeMap, VecDeque},
+    fs,
+    path::PathBuf,
+    sync::Weak,
+    thread,
+    time::{Duration, SystemTime},
+};
+
+const POLL_INTERVAL: Duration = Duration::from_millis(500);
+
+type Snapshot = BTreeMap<PathBuf, (Option<SystemTime>, u64)>;
+
+/// Polls a file, or the entries of a directory, and yields the paths that were
+/// created, modified or removed since the previous poll. Ends once `alive` can no
+/// longer be upgraded.
+pub(crate) struct Watcher {
+    path: PathBuf,
+    alive: Weak<()>,
+    snapshot: Snapshot,
+    changes: VecDeque<PathBuf>,
+}
+
+impl Watcher {
+    pub(crate) fn new(path: PathBuf, alive: Weak<()>) -> Self {
+        Self {
+            snapshot: snapshot(&path),
+            path,
+            alive,
+            changes: VecDeque::new(),
+        }
+    }
+}
+
+fn snapshot(path: &PathBuf) -> Snapshot {
+    let mut snapshot = Snapshot::new();
+    let mut insert = |path: PathBuf| {
+        if let Ok(metadata) = fs::metadata(&path) {
+            snapshot.insert(path, (metadata.modified().ok(), metadata.len()));
+        }
+    };
+    match fs::read_dir(path) {
+        Ok(entries) => {
+            for entry in entries.flatten() {
+                insert(entry.path());
+            }
+        }
+        Err(_) => insert(path.clone()),
+    }
+    snapshot
+}
+
+impl Iterator for Watcher {
+    type Item = PathBuf;
+
+    fn next(&mut self) -> Option<PathBuf> {
+        while self.changes.is_empty() {
+            thread::sleep(POLL_INTERVAL);
+            // the subscription was dropped
+            self.alive.upgrade()?;
+            let snapshot = snapshot(&self.path);
+            for (path, state) in &snapshot {
+                if self.snapshot.get(path) != Some(state) {
+                    self.changes.push_back(path.clone());
+                }
+            }
+            for path in self.snapshot.keys() {
+                if !snapshot.contains_key(path) {
+                    self.changes.push_back(path.clone());
+                }
+            }
+            self.snapshot = snapshot;
+        }
+        self.changes.pop_front()
+    }
+}
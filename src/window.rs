@@ -0,0 +1,148 @@
+use image::{DynamicImage, RgbaImage};
+
+/// How the window of `Program::run` is created.
+///
+/// ```ignore
+/// let settings = WindowSettings::new("Plot")
+///     .size(800, 600)
+///     .min_size(400, 300)
+///     .always_on_top(true);
+/// ```
+#[derive(Clone)]
+pub struct WindowSettings {
+    pub(crate) title: String,
+    pub(crate) size: (u32, u32),
+    pub(crate) position: Option<(i32, i32)>,
+    pub(crate) min_size: Option<(u32, u32)>,
+    pub(crate) max_size: Option<(u32, u32)>,
+    pub(crate) resizable: bool,
+    pub(crate) decorated: bool,
+    pub(crate) maximized: bool,
+    pub(crate) fullscreen: Option<usize>,
+    pub(crate) transparent: bool,
+    pub(crate) always_on_top: bool,
+    pub(crate) icon: Option<RgbaImage>,
+    pub(crate) samples: Option<u32>,
+}
+
+impl WindowSettings {
+    pub fn new(title: &str) -> Self {
+        Self {
+            title: title.to_string(),
+            size: (600, 400),
+            position: None,
+            min_size: None,
+            max_size: None,
+            resizable: true,
+            decorated: true,
+            maximized: false,
+            fullscreen: None,
+            transparent: false,
+            always_on_top: false,
+            icon: None,
+            samples: Some(4),
+        }
+    }
+
+    /// Size of the content area in screen coordinates.
+    pub fn size(mut self, width: u32, height: u32) -> Self {
+        self.size = (width, height);
+        self
+    }
+
+    /// Position of the content area in screen coordinates. Left to the system by default.
+    pub fn position(mut self, x: i32, y: i32) -> Self {
+        self.position = Some((x, y));
+        self
+    }
+
+    pub fn min_size(mut self, width: u32, height: u32) -> Self {
+        self.min_size = Some((width, height));
+        self
+    }
+
+    pub fn max_size(mut self, width: u32, height: u32) -> Self {
+        self.max_size = Some((width, height));
+        self
+    }
+
+    pub fn resizable(mut self, resizable: bool) -> Self {
+        self.resizable = resizable;
+        self
+    }
+
+    /// Whether the window has a title bar and borders.
+    pub fn decorated(mut self, decorated: bool) -> Self {
+        self.decorated = decorated;
+        self
+    }
+
+    pub fn maximized(mut self, maximized: bool) -> Self {
+        self.maximized = maximized;
+        self
+    }
+
+    /// Covers the monitor with the given index, 0 being the primary one, at its current
+    /// video mode. The size and position are ignored.
+    pub fn fullscreen(mut self, monitor: usize) -> Self {
+        self.fullscreen = Some(monitor);
+        self
+    }
+
+    /// Lets the desktop show through where the window is drawn with transparent colors.
+    pub fn transparent(mut self, transparent: bool) -> Self {
+        self.transparent = transparent;
+        self
+    }
+
+    pub fn always_on_top(mut self, always_on_top: bool) -> Self {
+        self.always_on_top = always_on_top;
+        self
+    }
+
+    /// Icon of the window and its taskbar entry. Ignored on macOS and Wayland.
+    pub fn icon(mut self, icon: DynamicImage) -> Self {
+        self.icon = Some(icon.into_rgba8());
+        self
+    }
+
+    /// Number of samples per pixel used for antialiasing, `None` to disable it.
+    pub fn samples(mut self, samples: Option<u32>) -> Self {
+        self.samples = samples;
+        self
+    }
+}
+
+impl Default for WindowSettings {
+    fn default() -> Self {
+        Self::new("show")
+    }
+}
+
+/// Changes the window at runtime, see `Command::Window`. Sizes and positions are in screen
+/// coordinates.
+#[derive(Clone, PartialEq, Debug)]
+pub enum WindowCommand {
+    SetTitle(String),
+    Resize(u32, u32),
+    Move(i32, i32),
+    Maximize,
+    Minimize,
+    /// Undoes `Maximize` or `Minimize`.
+    Restore,
+    /// Switches between a window and fullscreen on the primary monitor.
+    ToggleFullscreen,
+    /// Highlights the window in the taskbar, or bounces the dock icon on macOS.
+    RequestAttention,
+}
+
+pub(crate) fn pixel_image(icon: &RgbaImage) -> glfw::PixelImage {
+    glfw::PixelImage {
+        width: icon.width(),
+        height: icon.height(),
+        pixels: icon
+            .pixels()
+            .map(|pixel| u32::from_ne_bytes(pixel.0))
+            .collect(),
+    }
+}